@@ -0,0 +1,62 @@
+//! Compares serving a JSON weather response from the memo of `CachedSolesData` with serializing it per request.
+//! Run with `cargo bench`.
+
+use std::collections::HashMap;
+
+use api::{CachedSolesData, Sole, SoleData, SolesData, TemperatureUnit};
+use criterion::{BatchSize, Criterion, black_box, criterion_group, criterion_main};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+struct Feed {
+    soles: Vec<SoleData>,
+}
+
+fn soles_data() -> SolesData {
+    let feed: Feed = serde_json::from_str(include_str!("../fixtures/msl.json")).unwrap();
+    let latest = feed.soles.into_iter().next().unwrap();
+    [(("msl".to_string(), Sole::from(4804)), latest)]
+        .into_iter()
+        .collect()
+}
+
+fn weather_json(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    let data = soles_data();
+
+    c.bench_function("weather_json/serialized", |b| {
+        b.iter_batched(
+            || CachedSolesData::new(data.clone(), HashMap::new(), HashMap::new()),
+            |cache| {
+                runtime.block_on(async {
+                    black_box(
+                        cache
+                            .weather_json("msl", 4804, TemperatureUnit::Celsius)
+                            .await
+                            .unwrap(),
+                    )
+                })
+            },
+            BatchSize::SmallInput,
+        )
+    });
+
+    let cache = CachedSolesData::new(data, HashMap::new(), HashMap::new());
+    c.bench_function("weather_json/memoized", |b| {
+        b.iter(|| {
+            runtime.block_on(async {
+                black_box(
+                    cache
+                        .weather_json("msl", 4804, TemperatureUnit::Celsius)
+                        .await
+                        .unwrap(),
+                )
+            })
+        })
+    });
+}
+
+criterion_group!(benches, weather_json);
+criterion_main!(benches);
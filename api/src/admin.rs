@@ -0,0 +1,43 @@
+use axum::http::{HeaderMap, header};
+
+/// Whether the request carries `Authorization: Bearer <token>` with the configured admin token.
+/// Always false when no admin token is configured, so admin routes are closed by default.
+pub fn is_authorized(headers: &HeaderMap, admin_token: Option<&str>) -> bool {
+    let Some(admin_token) = admin_token else {
+        return false;
+    };
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| constant_time_eq(token.trim().as_bytes(), admin_token.as_bytes()))
+}
+
+// Compares every byte regardless of where they differ, so response times do not reveal how much of a token matched
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::HeaderValue;
+
+    use super::*;
+
+    fn headers(authorization: &'static str) -> HeaderMap {
+        HeaderMap::from_iter([(
+            header::AUTHORIZATION,
+            HeaderValue::from_static(authorization),
+        )])
+    }
+
+    #[test]
+    fn test_is_authorized() {
+        assert!(is_authorized(&headers("Bearer s3cret"), Some("s3cret")));
+        assert!(!is_authorized(&headers("Bearer s3cret"), None));
+        assert!(!is_authorized(&headers("Bearer s3cre"), Some("s3cret")));
+        assert!(!is_authorized(&headers("Bearer s3cretx"), Some("s3cret")));
+        assert!(!is_authorized(&headers("Basic s3cret"), Some("s3cret")));
+        assert!(!is_authorized(&HeaderMap::new(), Some("s3cret")));
+    }
+}
@@ -0,0 +1,90 @@
+use crate::{SoleData, SolesData};
+
+/// Checks a row for physically implausible values, returning a description of each
+fn anomalies(data: &SoleData) -> Vec<&'static str> {
+    let mut anomalies = Vec::new();
+    if let (Some(min_temp), Some(max_temp)) = (data.min_temp, data.max_temp)
+        && min_temp > max_temp
+    {
+        anomalies.push("min_temp is above max_temp");
+    }
+    if let (Some(sunrise), Some(sunset)) = (data.sunrise, data.sunset)
+        && sunrise > sunset
+    {
+        anomalies.push("sunrise is after sunset");
+    }
+    anomalies
+}
+
+/// Flags rows with physically implausible values, logging a warning for each. Flagged rows are dropped when
+/// `drop_anomalies` is set. Returns the remaining soles data and the number of flagged rows.
+pub fn validate_soles(soles: SolesData, drop_anomalies: bool) -> (SolesData, usize) {
+    let mut count = 0;
+    let soles = soles
+        .into_iter()
+        .filter(|((rover, sol), data)| {
+            let anomalies = anomalies(data);
+            if anomalies.is_empty() {
+                return true;
+            }
+            count += 1;
+            tracing::warn!(
+                "Implausible soles data for rover {rover} sol {}: {}",
+                sol.0,
+                anomalies.join(", ")
+            );
+            !drop_anomalies
+        })
+        .collect();
+    (soles, count)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveTime;
+
+    use super::*;
+    use crate::Sole;
+
+    fn soles() -> SolesData {
+        [
+            SoleData::fixture(4803),
+            SoleData {
+                min_temp: Some(-5),
+                max_temp: Some(-70),
+                ..SoleData::fixture(4804)
+            },
+            SoleData {
+                sunrise: NaiveTime::from_hms_opt(18, 0, 0),
+                min_temp: Some(-5),
+                max_temp: Some(-70),
+                ..SoleData::fixture(4805)
+            },
+        ]
+        .into_iter()
+        .map(|data| (("msl".to_string(), data.sol.clone()), data))
+        .collect()
+    }
+
+    #[test]
+    fn test_validate_soles() {
+        let (kept, count) = validate_soles(soles(), false);
+        assert_eq!(count, 2);
+        assert_eq!(kept.len(), 3);
+
+        let (kept, count) = validate_soles(soles(), true);
+        assert_eq!(count, 2);
+        assert_eq!(kept.len(), 1);
+        assert!(kept.contains_key(&("msl".to_string(), Sole(4803))));
+    }
+
+    #[test]
+    fn test_anomalies_ignore_missing_temps() {
+        let data = SoleData {
+            min_temp: Some(-5),
+            max_temp: None,
+            ..SoleData::fixture(4804)
+        };
+        assert!(anomalies(&data).is_empty());
+    }
+}
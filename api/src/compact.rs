@@ -0,0 +1,30 @@
+use axum::{
+    extract::{Query, Request},
+    middleware::Next,
+    response::Response,
+};
+use serde::Deserialize;
+
+tokio::task_local! {
+    static EXPLICIT_NULLS: bool;
+}
+
+#[derive(Debug, Deserialize)]
+struct CompactQuery {
+    compact: Option<bool>,
+}
+
+/// Whether the response being built serializes missing values as `null` and keeps every optional field,
+/// as requested with `compact=false`. By default missing values are `N/A` and optional fields are left out.
+pub fn explicit_nulls() -> bool {
+    EXPLICIT_NULLS
+        .try_with(|explicit| *explicit)
+        .unwrap_or(false)
+}
+
+/// Middleware that makes [`explicit_nulls`] return true while handling requests with `compact=false`
+pub async fn compact(request: Request, next: Next) -> Response {
+    let explicit = Query::<CompactQuery>::try_from_uri(request.uri())
+        .is_ok_and(|Query(query)| query.compact == Some(false));
+    EXPLICIT_NULLS.scope(explicit, next.run(request)).await
+}
@@ -0,0 +1,215 @@
+use axum::{
+    extract::FromRequestParts,
+    http::{StatusCode, header, request::Parts},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+
+/// Value of the `If-None-Match` request header, if present and valid
+pub struct IfNoneMatch(pub Option<String>);
+
+impl<S> FromRequestParts<S> for IfNoneMatch
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(IfNoneMatch(
+            parts
+                .headers
+                .get(header::IF_NONE_MATCH)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string),
+        ))
+    }
+}
+
+/// Value of the `If-Modified-Since` request header. Malformed dates are ignored.
+pub struct IfModifiedSince(pub Option<DateTime<Utc>>);
+
+impl<S> FromRequestParts<S> for IfModifiedSince
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(IfModifiedSince(
+            parts
+                .headers
+                .get(header::IF_MODIFIED_SINCE)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_http_date),
+        ))
+    }
+}
+
+/// Formats a timestamp as an HTTP-date, e.g. `Tue, 10 Feb 2026 00:00:00 GMT`
+pub fn http_date(datetime: DateTime<Utc>) -> String {
+    datetime.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Parses an HTTP-date, returning `None` when malformed
+pub fn parse_http_date(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(value)
+        .ok()
+        .map(|datetime| datetime.to_utc())
+}
+
+/// Strong ETag derived from when the cached data was last updated and the representation served, e.g. the
+/// negotiated format and the query string, so JSON and CSV of the same data never share an ETag
+pub fn etag_for(updated_at: DateTime<Utc>, representation: &str) -> String {
+    format!(
+        "\"{}-{:016x}\"",
+        updated_at.timestamp_micros(),
+        fnv1a(representation.as_bytes())
+    )
+}
+
+// 64-bit FNV-1a, which unlike the std hashers is stable across Rust releases, so ETags survive upgrades
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// Checks whether an `If-None-Match` header value matches the ETag, using weak comparison
+pub fn etag_matches(if_none_match: &str, etag: &str) -> bool {
+    if_none_match.split(',').map(str::trim).any(|candidate| {
+        candidate == "*" || candidate.strip_prefix("W/").unwrap_or(candidate) == etag
+    })
+}
+
+/// Sets the ETag on successful responses, or replaces them with `304 Not Modified` if the client already has it
+pub fn with_etag(response: Response, etag: &str, if_none_match: Option<&str>) -> Response {
+    if !response.status().is_success() {
+        return response;
+    }
+
+    if if_none_match.is_some_and(|if_none_match| etag_matches(if_none_match, etag)) {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, etag)]).into_response();
+    }
+
+    let mut response = response;
+    if let Ok(value) = etag.parse() {
+        response.headers_mut().insert(header::ETAG, value);
+    }
+    response
+}
+
+/// Sets `Last-Modified` on successful responses, or replaces them with `304 Not Modified` if not modified since
+/// `If-Modified-Since`. HTTP-dates have second precision so sub-second changes are ignored.
+pub fn with_last_modified(
+    response: Response,
+    updated_at: DateTime<Utc>,
+    if_modified_since: Option<DateTime<Utc>>,
+) -> Response {
+    if !response.status().is_success() {
+        return response;
+    }
+
+    let last_modified = http_date(updated_at);
+    if if_modified_since.is_some_and(|since| updated_at.timestamp() <= since.timestamp()) {
+        let mut not_modified = (
+            StatusCode::NOT_MODIFIED,
+            [(header::LAST_MODIFIED, last_modified)],
+        )
+            .into_response();
+        if let Some(etag) = response.headers().get(header::ETAG) {
+            not_modified
+                .headers_mut()
+                .insert(header::ETAG, etag.clone());
+        }
+        return not_modified;
+    }
+
+    let mut response = response;
+    if let Ok(value) = last_modified.parse() {
+        response.headers_mut().insert(header::LAST_MODIFIED, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_etag_matches() {
+        let updated_at = DateTime::from_timestamp(1_770_681_600, 0).unwrap();
+        let etag = etag_for(updated_at, "Json?date=2026-02-10");
+        assert!(etag.starts_with("\"1770681600000000-"));
+        assert_ne!(etag, etag_for(updated_at, "Csv?date=2026-02-10"));
+        assert_ne!(
+            etag,
+            etag_for(updated_at, "Json?date=2026-02-10&pretty=true")
+        );
+
+        assert!(etag_matches(&etag, &etag));
+        assert!(etag_matches(&format!("W/{etag}"), &etag));
+        assert!(etag_matches(&format!("\"other\", {etag}"), &etag));
+        assert!(etag_matches("*", &etag));
+        assert!(!etag_matches("\"1770681600000000\"", &etag));
+        assert!(!etag_matches(etag.trim_matches('"'), &etag));
+    }
+
+    #[test]
+    fn test_with_etag() {
+        let etag = "\"1\"";
+
+        let response = with_etag(StatusCode::OK.into_response(), etag, None);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::ETAG], etag);
+
+        let response = with_etag(StatusCode::OK.into_response(), etag, Some(etag));
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        let response = with_etag(StatusCode::BAD_REQUEST.into_response(), etag, Some(etag));
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(response.headers().get(header::ETAG).is_none());
+    }
+
+    #[test]
+    fn test_http_date() {
+        let datetime = DateTime::from_timestamp(1_770_681_600, 0).unwrap();
+        assert_eq!(http_date(datetime), "Tue, 10 Feb 2026 00:00:00 GMT");
+        assert_eq!(
+            parse_http_date("Tue, 10 Feb 2026 00:00:00 GMT"),
+            Some(datetime)
+        );
+        assert_eq!(parse_http_date("yesterday"), None);
+    }
+
+    #[test]
+    fn test_with_last_modified() {
+        let updated_at = DateTime::from_timestamp(1_770_681_600, 500_000_000).unwrap();
+        let before = DateTime::from_timestamp(1_770_681_599, 0).unwrap();
+        let same_second = DateTime::from_timestamp(1_770_681_600, 0).unwrap();
+
+        let response = with_last_modified(StatusCode::OK.into_response(), updated_at, None);
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::LAST_MODIFIED],
+            "Tue, 10 Feb 2026 00:00:00 GMT"
+        );
+
+        let response = with_last_modified(StatusCode::OK.into_response(), updated_at, Some(before));
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = with_last_modified(
+            StatusCode::OK.into_response(),
+            updated_at,
+            Some(same_second),
+        );
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+        let response = with_last_modified(
+            StatusCode::BAD_REQUEST.into_response(),
+            updated_at,
+            Some(same_second),
+        );
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(response.headers().get(header::LAST_MODIFIED).is_none());
+    }
+}
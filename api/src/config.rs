@@ -0,0 +1,335 @@
+use std::{net::SocketAddr, path::PathBuf, time::Duration};
+
+use anyhow::{Context, anyhow};
+
+const DEFAULT_BIND_ADDR: &str = "0.0.0.0:3000";
+const DEFAULT_FEED_URL: &str = "https://mars.nasa.gov/rss/api/";
+const DEFAULT_FEED_CATEGORY: &str = "msl";
+// One hour. Intervals are built with `Duration::from_secs`, which unlike `Duration::from_hours` is stable on every
+// supported toolchain.
+const DEFAULT_REFRESH_SECS: u64 = 3600;
+const DEFAULT_FETCH_TIMEOUT_SECS: u64 = 30;
+const DEFAULT_RATE_LIMIT_PER_MINUTE: u32 = 60;
+const DEFAULT_RAW_HISTORY_SIZE: usize = 10;
+const DEFAULT_MAX_SOLS_PER_RESPONSE: usize = 366;
+// Pinned so /docs does not change under us with new releases of Swagger UI
+const DEFAULT_SWAGGER_UI_URL: &str = "https://unpkg.com/swagger-ui-dist@5.17.14";
+
+/// Runtime configuration read from environment variables at startup
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Address the server listens on. Set with `LINX_BIND_ADDR`.
+    pub bind_addr: SocketAddr,
+    /// Base url of the NASA weather feed. Set with `LINX_FEED_URL`.
+    pub feed_url: String,
+    /// Rover categories of the NASA weather feed, e.g. `msl`. Set with comma-separated `LINX_FEED_CATEGORY`.
+    pub feed_categories: Vec<String>,
+    /// Origins allowed to make cross-origin requests, `*` for any. Set with comma-separated `LINX_CORS_ORIGINS`.
+    pub cors_origins: Vec<String>,
+    /// Path of SQLite database used as fallback when NASA is unreachable at startup. Set with `LINX_DB_PATH`.
+    pub db_path: Option<PathBuf>,
+    /// How often cached soles data is refreshed. Set in seconds with `LINX_REFRESH_SECS`.
+    pub refresh_interval: Duration,
+    /// Timeout for connecting to and fetching from the NASA feed. Set in seconds with `LINX_FETCH_TIMEOUT_SECS`.
+    pub fetch_timeout: Duration,
+    /// Requests per minute allowed for each client IP on weather routes, 0 disables the limit.
+    /// Set with `LINX_RATE_LIMIT_PER_MINUTE`.
+    pub rate_limit_per_minute: u32,
+    /// PEM certificate chain and private key to serve HTTPS with. Set with `LINX_TLS_CERT` and `LINX_TLS_KEY`.
+    pub tls: Option<TlsPaths>,
+    /// Whether rows with physically implausible values are dropped instead of only logged. Set with `LINX_DROP_ANOMALIES`.
+    pub drop_anomalies: bool,
+    /// Whether startup keeps retrying instead of exiting when no soles data is available. Set with `LINX_WAIT_FOR_DATA`.
+    pub wait_for_data: bool,
+    /// Whether the background updater is skipped, keeping the data loaded at startup. Set with `LINX_DISABLE_UPDATER`.
+    pub disable_updater: bool,
+    /// Number of payloads fetched from NASA kept for /raw/history, 0 disables the history.
+    /// Set with `LINX_RAW_HISTORY_SIZE`.
+    pub raw_history_size: usize,
+    /// Most sols a single range, window or batch response may contain. Set with `LINX_MAX_SOLS_PER_RESPONSE`.
+    pub max_sols_per_response: usize,
+    /// Bearer token required by /admin routes, which reject every request when unset. Set with `LINX_ADMIN_TOKEN`.
+    pub admin_token: Option<String>,
+    /// Base url of the Swagger UI assets loaded by /docs, e.g. a self-hosted copy of `swagger-ui-dist` for use
+    /// without internet access. Set with `LINX_SWAGGER_UI_URL`.
+    pub swagger_ui_url: String,
+}
+
+/// Paths of the PEM files used for TLS
+#[derive(Debug, Clone)]
+pub struct TlsPaths {
+    pub cert: PathBuf,
+    pub key: PathBuf,
+}
+
+impl Config {
+    pub fn from_env() -> anyhow::Result<Self> {
+        let bind_addr = env_or_default("LINX_BIND_ADDR", DEFAULT_BIND_ADDR);
+        let bind_addr = bind_addr.parse::<SocketAddr>().with_context(|| {
+            format!("Invalid LINX_BIND_ADDR '{bind_addr}'. Expected e.g. 0.0.0.0:3000")
+        })?;
+
+        let tls = match (env_path("LINX_TLS_CERT"), env_path("LINX_TLS_KEY")) {
+            (Some(cert), Some(key)) => Some(TlsPaths { cert, key }),
+            (None, None) => None,
+            _ => {
+                return Err(anyhow!(
+                    "LINX_TLS_CERT and LINX_TLS_KEY must either both be set or both be unset"
+                ));
+            }
+        };
+
+        Ok(Config {
+            bind_addr,
+            feed_url: env_or_default("LINX_FEED_URL", DEFAULT_FEED_URL),
+            feed_categories: split_list(&env_or_default(
+                "LINX_FEED_CATEGORY",
+                DEFAULT_FEED_CATEGORY,
+            )),
+            cors_origins: split_list(&env_or_default("LINX_CORS_ORIGINS", "")),
+            db_path: env_path("LINX_DB_PATH"),
+            refresh_interval: parse_refresh_interval(std::env::var("LINX_REFRESH_SECS").ok()),
+            fetch_timeout: parse_secs(
+                "LINX_FETCH_TIMEOUT_SECS",
+                std::env::var("LINX_FETCH_TIMEOUT_SECS").ok(),
+                DEFAULT_FETCH_TIMEOUT_SECS,
+            ),
+            rate_limit_per_minute: parse_rate_limit(
+                std::env::var("LINX_RATE_LIMIT_PER_MINUTE").ok(),
+            ),
+            tls,
+            drop_anomalies: parse_bool(
+                "LINX_DROP_ANOMALIES",
+                std::env::var("LINX_DROP_ANOMALIES").ok(),
+            ),
+            wait_for_data: parse_bool(
+                "LINX_WAIT_FOR_DATA",
+                std::env::var("LINX_WAIT_FOR_DATA").ok(),
+            ),
+            disable_updater: parse_bool(
+                "LINX_DISABLE_UPDATER",
+                std::env::var("LINX_DISABLE_UPDATER").ok(),
+            ),
+            raw_history_size: parse_raw_history_size(std::env::var("LINX_RAW_HISTORY_SIZE").ok()),
+            max_sols_per_response: parse_max_sols_per_response(
+                std::env::var("LINX_MAX_SOLS_PER_RESPONSE").ok(),
+            ),
+            admin_token: std::env::var("LINX_ADMIN_TOKEN")
+                .ok()
+                .map(|token| token.trim().to_string())
+                .filter(|token| !token.is_empty()),
+            swagger_ui_url: env_or_default("LINX_SWAGGER_UI_URL", DEFAULT_SWAGGER_UI_URL)
+                .trim_end_matches('/')
+                .to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+impl Config {
+    /// Default configuration without TLS, database or rate limit for tests
+    pub fn fixture() -> Self {
+        Config {
+            bind_addr: DEFAULT_BIND_ADDR.parse().unwrap(),
+            feed_url: DEFAULT_FEED_URL.to_string(),
+            feed_categories: vec![DEFAULT_FEED_CATEGORY.to_string()],
+            cors_origins: Vec::new(),
+            db_path: None,
+            refresh_interval: Duration::from_secs(DEFAULT_REFRESH_SECS),
+            fetch_timeout: Duration::from_secs(DEFAULT_FETCH_TIMEOUT_SECS),
+            rate_limit_per_minute: 0,
+            tls: None,
+            drop_anomalies: false,
+            wait_for_data: false,
+            disable_updater: true,
+            raw_history_size: DEFAULT_RAW_HISTORY_SIZE,
+            max_sols_per_response: DEFAULT_MAX_SOLS_PER_RESPONSE,
+            admin_token: None,
+            swagger_ui_url: DEFAULT_SWAGGER_UI_URL.to_string(),
+        }
+    }
+}
+
+/// Parses refresh interval in seconds, falling back to the hourly default when unset or not a positive integer
+fn parse_refresh_interval(value: Option<String>) -> Duration {
+    parse_secs("LINX_REFRESH_SECS", value, DEFAULT_REFRESH_SECS)
+}
+
+/// Parses a duration in seconds of the env var `key`, falling back to the default when unset or not a positive integer
+fn parse_secs(key: &str, value: Option<String>, default_secs: u64) -> Duration {
+    let secs = match value.as_deref().map(|value| value.trim().parse::<u64>()) {
+        None => default_secs,
+        Some(Ok(secs)) if secs > 0 => secs,
+        Some(_) => {
+            tracing::warn!(
+                "Invalid {key} {value:?}. Expected a positive integer. Using {default_secs} seconds."
+            );
+            default_secs
+        }
+    };
+    Duration::from_secs(secs)
+}
+
+/// Parses requests allowed per minute, falling back to the default when unset or not a non-negative integer
+fn parse_rate_limit(value: Option<String>) -> u32 {
+    match value.as_deref().map(|value| value.trim().parse::<u32>()) {
+        None => DEFAULT_RATE_LIMIT_PER_MINUTE,
+        Some(Ok(limit)) => limit,
+        Some(Err(_)) => {
+            tracing::warn!(
+                "Invalid LINX_RATE_LIMIT_PER_MINUTE {value:?}. Expected a non-negative integer. Using {DEFAULT_RATE_LIMIT_PER_MINUTE}."
+            );
+            DEFAULT_RATE_LIMIT_PER_MINUTE
+        }
+    }
+}
+
+/// Parses number of kept payloads, falling back to the default when unset or not a non-negative integer
+fn parse_raw_history_size(value: Option<String>) -> usize {
+    match value.as_deref().map(|value| value.trim().parse::<usize>()) {
+        None => DEFAULT_RAW_HISTORY_SIZE,
+        Some(Ok(size)) => size,
+        Some(Err(_)) => {
+            tracing::warn!(
+                "Invalid LINX_RAW_HISTORY_SIZE {value:?}. Expected a non-negative integer. Using {DEFAULT_RAW_HISTORY_SIZE}."
+            );
+            DEFAULT_RAW_HISTORY_SIZE
+        }
+    }
+}
+
+/// Parses most sols per response, falling back to the default when unset or not a positive integer
+fn parse_max_sols_per_response(value: Option<String>) -> usize {
+    match value.as_deref().map(|value| value.trim().parse::<usize>()) {
+        None => DEFAULT_MAX_SOLS_PER_RESPONSE,
+        Some(Ok(max)) if max > 0 => max,
+        Some(_) => {
+            tracing::warn!(
+                "Invalid LINX_MAX_SOLS_PER_RESPONSE {value:?}. Expected a positive integer. Using {DEFAULT_MAX_SOLS_PER_RESPONSE}."
+            );
+            DEFAULT_MAX_SOLS_PER_RESPONSE
+        }
+    }
+}
+
+/// Parses a flag of the env var `key`, falling back to false when unset or not `true`/`false`/`1`/`0`
+fn parse_bool(key: &str, value: Option<String>) -> bool {
+    match value
+        .as_deref()
+        .map(|value| value.trim().to_ascii_lowercase())
+    {
+        None => false,
+        Some(value) if value == "true" || value == "1" => true,
+        Some(value) if value == "false" || value == "0" || value.is_empty() => false,
+        Some(value) => {
+            tracing::warn!("Invalid {key} {value:?}. Expected true or false. Using false.");
+            false
+        }
+    }
+}
+
+/// Splits a comma-separated list, ignoring empty entries
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn env_path(key: &str) -> Option<PathBuf> {
+    std::env::var(key)
+        .ok()
+        .filter(|path| !path.trim().is_empty())
+        .map(PathBuf::from)
+}
+
+fn env_or_default(key: &str, default: &str) -> String {
+    match std::env::var(key) {
+        Ok(value) if !value.trim().is_empty() => value,
+        _ => default.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_refresh_interval() {
+        assert_eq!(parse_refresh_interval(None), Duration::from_secs(3600));
+        assert_eq!(
+            parse_refresh_interval(Some("600".to_string())),
+            Duration::from_secs(600)
+        );
+        assert_eq!(
+            parse_refresh_interval(Some("0".to_string())),
+            Duration::from_secs(3600)
+        );
+        assert_eq!(
+            parse_refresh_interval(Some("-5".to_string())),
+            Duration::from_secs(3600)
+        );
+        assert_eq!(
+            parse_refresh_interval(Some("hourly".to_string())),
+            Duration::from_secs(3600)
+        );
+    }
+
+    #[test]
+    fn test_parse_secs() {
+        assert_eq!(
+            parse_secs("LINX_FETCH_TIMEOUT_SECS", None, 30),
+            Duration::from_secs(30)
+        );
+        assert_eq!(
+            parse_secs("LINX_FETCH_TIMEOUT_SECS", Some("5".to_string()), 30),
+            Duration::from_secs(5)
+        );
+        assert_eq!(
+            parse_secs("LINX_FETCH_TIMEOUT_SECS", Some("0".to_string()), 30),
+            Duration::from_secs(30)
+        );
+    }
+
+    #[test]
+    fn test_parse_bool() {
+        assert!(!parse_bool("LINX_DROP_ANOMALIES", None));
+        assert!(parse_bool("LINX_DROP_ANOMALIES", Some("true".to_string())));
+        assert!(parse_bool("LINX_DROP_ANOMALIES", Some("1".to_string())));
+        assert!(!parse_bool(
+            "LINX_DROP_ANOMALIES",
+            Some("FALSE".to_string())
+        ));
+        assert!(!parse_bool(
+            "LINX_DROP_ANOMALIES",
+            Some("yes please".to_string())
+        ));
+    }
+
+    #[test]
+    fn test_parse_rate_limit() {
+        assert_eq!(parse_rate_limit(None), 60);
+        assert_eq!(parse_rate_limit(Some("120".to_string())), 120);
+        assert_eq!(parse_rate_limit(Some("0".to_string())), 0);
+        assert_eq!(parse_rate_limit(Some("-1".to_string())), 60);
+        assert_eq!(parse_rate_limit(Some("lots".to_string())), 60);
+    }
+
+    #[test]
+    fn test_parse_max_sols_per_response() {
+        assert_eq!(parse_max_sols_per_response(None), 366);
+        assert_eq!(parse_max_sols_per_response(Some("30".to_string())), 30);
+        assert_eq!(parse_max_sols_per_response(Some("0".to_string())), 366);
+        assert_eq!(parse_max_sols_per_response(Some("all".to_string())), 366);
+    }
+
+    #[test]
+    fn test_parse_raw_history_size() {
+        assert_eq!(parse_raw_history_size(None), 10);
+        assert_eq!(parse_raw_history_size(Some("3".to_string())), 3);
+        assert_eq!(parse_raw_history_size(Some("0".to_string())), 0);
+        assert_eq!(parse_raw_history_size(Some("-1".to_string())), 10);
+    }
+}
@@ -1,19 +1,87 @@
+use anyhow::anyhow;
 use chrono::Utc;
+use serde::Serialize;
+use utoipa::ToSchema;
 
 // 2012-08-06 05:17:00 UTC
 const CURIOSTY_LANDING_DATE_IN_UNIX_TS: i64 = 1344230220;
 
-/// Calculated no of Martian sols elapsed since Curiosity landing date
-pub fn calculate_no_of_martian_sol_elapsed(datetime: chrono::DateTime<Utc>) -> i64 {
+const SOL_IN_SECS: f64 = 88775.245;
+
+/// How much longer a sol is than an Earth day, in seconds. Adjacent dates map to the same sol roughly every 37 days.
+pub const SOL_DRIFT_SECS: f64 = 2375.245;
+
+/// How dates are converted to sols, served on /about/conversion
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ConversionInfo {
+    /// Curiosity's landing, the start of sol 0
+    pub landing_date: chrono::DateTime<Utc>,
+    #[schema(example = 88775.245)]
+    pub sol_length_seconds: f64,
+    #[schema(example = 2375.245)]
+    pub sol_drift_seconds: f64,
+    pub formula: &'static str,
+    pub description: String,
+}
+
+impl ConversionInfo {
+    pub fn new() -> Self {
+        ConversionInfo {
+            landing_date: chrono::DateTime::from_timestamp(CURIOSTY_LANDING_DATE_IN_UNIX_TS, 0)
+                .expect("Landing date is a valid timestamp"),
+            sol_length_seconds: SOL_IN_SECS,
+            sol_drift_seconds: SOL_DRIFT_SECS,
+            formula: "sol = ceil((timestamp - landing_timestamp) / sol_length_seconds)",
+            description: format!(
+                "Sols are counted on Curiosity's mission clock from its landing at 2012-08-06 05:17:00 UTC, \
+                 which is sol 0. A date is converted by dividing the seconds since the landing by the length of a sol, \
+                 {SOL_IN_SECS} seconds, and rounding up. Instants up to and including the end of a sol map to that sol, \
+                 so any time after the landing maps to sol 1 or later. Dates without a time are taken as midnight UTC. \
+                 A sol is {SOL_DRIFT_SECS} seconds longer than an Earth day, so adjacent dates sometimes map to the same sol."
+            ),
+        }
+    }
+}
+
+/// Calculated no of Martian sols elapsed since Curiosity landing date. Fails for dates before the landing.
+pub fn calculate_no_of_martian_sol_elapsed(datetime: chrono::DateTime<Utc>) -> anyhow::Result<i64> {
+    if datetime.timestamp() < CURIOSTY_LANDING_DATE_IN_UNIX_TS {
+        return Err(anyhow!(
+            "Date {datetime} is before Curiosity landing date 2012-08-06 05:17:00 UTC"
+        ));
+    }
+
     // formula: ⌈(Δ • 86400 / 88775.245)⌉ where Δ is diff between date and Curiosity landing date in days
     let diff: f64 = (datetime.timestamp() - CURIOSTY_LANDING_DATE_IN_UNIX_TS) as f64;
-    return (diff / 88775.245).ceil() as i64;
+    Ok((diff / SOL_IN_SECS).ceil() as i64)
+}
+
+/// Inverse of [`calculate_no_of_martian_sol_elapsed`]: the last second in UTC that maps to the sol.
+/// Fails for negative sols and sols too far from the landing date to be represented.
+pub fn earth_date_for_sol(sol: i64) -> anyhow::Result<chrono::DateTime<Utc>> {
+    if sol < 0 {
+        return Err(anyhow!("Sol {sol} is before Curiosity landing"));
+    }
+
+    // formula: landing + sol • 88775.245 seconds, rounded down so the result maps back to the sol
+    let offset = (sol as f64 * SOL_IN_SECS).floor() as i64;
+    CURIOSTY_LANDING_DATE_IN_UNIX_TS
+        .checked_add(offset)
+        .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+        .ok_or_else(|| anyhow!("Sol {sol} is too far in the future to be converted"))
+}
+
+/// Approximate start and end of a sol in UTC to the second. Instants after `start` up to and including `end` map to the sol.
+/// None for sols that can not be converted.
+pub fn sol_span(sol: i64) -> Option<(chrono::DateTime<Utc>, chrono::DateTime<Utc>)> {
+    Some((
+        earth_date_for_sol(sol - 1).ok()?,
+        earth_date_for_sol(sol).ok()?,
+    ))
 }
 
 #[cfg(test)]
 mod tests {
-    use std::ops::Add;
-
     use super::*;
 
     #[test]
@@ -23,7 +91,8 @@ mod tests {
         let date = chrono::DateTime::from_timestamp(CURIOSTY_LANDING_DATE_IN_UNIX_TS + ts, 0)
             .expect("Failed to create Datetime");
 
-        let martian_sols = calculate_no_of_martian_sol_elapsed(date);
+        let martian_sols =
+            calculate_no_of_martian_sol_elapsed(date).expect("Failed to calculate sols");
 
         // Should be 669 because of ceil func
         assert_eq!(martian_sols, 669);
@@ -33,9 +102,67 @@ mod tests {
             .expect("Failed to parse date")
             .to_utc();
 
-        let martian_sols = calculate_no_of_martian_sol_elapsed(date);
+        let martian_sols =
+            calculate_no_of_martian_sol_elapsed(date).expect("Failed to calculate sols");
 
         // Newest API response
         assert_eq!(martian_sols, 4804);
     }
+
+    #[test]
+    fn test_conversion_info() {
+        let info = ConversionInfo::new();
+
+        assert_eq!(
+            info.landing_date,
+            chrono::DateTime::parse_from_rfc3339("2012-08-06T05:17:00Z").unwrap()
+        );
+        assert_eq!(info.sol_length_seconds, 88775.245);
+        assert!(info.description.contains("88775.245 seconds"));
+    }
+
+    #[test]
+    fn test_earth_date_for_sol_round_trip() {
+        for sol in [1, 2, 37, 668, 669, 1000, 4804, 4805, 10_000, 123_456] {
+            let date = earth_date_for_sol(sol).expect("Failed to convert sol");
+            assert_eq!(calculate_no_of_martian_sol_elapsed(date).unwrap(), sol);
+            assert_eq!(earth_date_for_sol(sol).unwrap(), date);
+        }
+
+        assert_eq!(
+            earth_date_for_sol(4804).unwrap().to_rfc3339(),
+            "2026-02-10T06:54:56+00:00"
+        );
+        assert!(earth_date_for_sol(-1).is_err());
+        assert!(earth_date_for_sol(i64::MAX).is_err());
+    }
+
+    #[test]
+    fn test_sol_span() {
+        let (start, end) = sol_span(4804).expect("Failed to calculate span");
+
+        assert_eq!(start.to_rfc3339(), "2026-02-09T06:15:21+00:00");
+        assert_eq!(end.to_rfc3339(), "2026-02-10T06:54:56+00:00");
+        assert_eq!(
+            calculate_no_of_martian_sol_elapsed(start + chrono::Duration::seconds(1)).unwrap(),
+            4804
+        );
+        assert_eq!(calculate_no_of_martian_sol_elapsed(end).unwrap(), 4804);
+        assert_eq!(
+            calculate_no_of_martian_sol_elapsed(end + chrono::Duration::seconds(1)).unwrap(),
+            4805
+        );
+    }
+
+    #[test]
+    fn test_martian_sol_formula_before_landing() {
+        let landing = chrono::DateTime::from_timestamp(CURIOSTY_LANDING_DATE_IN_UNIX_TS, 0)
+            .expect("Failed to create Datetime");
+        assert_eq!(calculate_no_of_martian_sol_elapsed(landing).unwrap(), 0);
+
+        let before_landing =
+            chrono::DateTime::from_timestamp(CURIOSTY_LANDING_DATE_IN_UNIX_TS - 1, 0)
+                .expect("Failed to create Datetime");
+        assert!(calculate_no_of_martian_sol_elapsed(before_landing).is_err());
+    }
 }
@@ -0,0 +1,71 @@
+use axum::http::{HeaderValue, Method, header};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use crate::request_id::X_REQUEST_ID;
+use crate::{X_DATA_UPDATED_AT, X_FILTERED_SOLS};
+
+/// Builds a CORS layer allowing GET requests, and POST requests with JSON bodies, from the given origins. `*` allows any origin.
+pub fn cors_layer(origins: &[String]) -> CorsLayer {
+    let allow_origin = if origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(origins.iter().filter_map(|origin| {
+            HeaderValue::from_str(origin)
+                .inspect_err(|err| tracing::warn!("Ignoring invalid CORS origin {origin}: {err}"))
+                .ok()
+        }))
+    };
+
+    CorsLayer::new()
+        .allow_methods([Method::GET, Method::POST])
+        .allow_headers([header::CONTENT_TYPE])
+        .expose_headers([X_REQUEST_ID.clone(), X_DATA_UPDATED_AT, X_FILTERED_SOLS])
+        .allow_origin(allow_origin)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{Router, body::Body, http::Request, routing::get};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    async fn allow_origin_header(origins: &[&str], origin: &str) -> Option<HeaderValue> {
+        let origins: Vec<String> = origins.iter().map(|origin| origin.to_string()).collect();
+        let app = Router::new()
+            .route("/", get(|| async { "Hello!" }))
+            .layer(cors_layer(&origins));
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/")
+                    .header(header::ORIGIN, origin)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .cloned()
+    }
+
+    #[tokio::test]
+    async fn test_cors_layer() {
+        assert_eq!(
+            allow_origin_header(&["https://example.com"], "https://example.com").await,
+            Some(HeaderValue::from_static("https://example.com"))
+        );
+        assert_eq!(
+            allow_origin_header(&["*"], "https://example.com").await,
+            Some(HeaderValue::from_static("*"))
+        );
+        assert_eq!(
+            allow_origin_header(&["https://example.com"], "https://other.com").await,
+            None
+        );
+    }
+}
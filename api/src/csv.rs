@@ -0,0 +1,80 @@
+use std::fmt::Write;
+
+use crate::SoleData;
+use crate::units::TemperatureUnit;
+
+// Columns added later are appended, so consumers reading by position keep working
+pub const CSV_HEADER: &str = "sol,terrestrial_date,min_temp,max_temp,sunrise,sunset,mean_temp";
+
+/// Serializes weather data as CSV with one row per sol. Sols without data and missing temperatures are left empty.
+pub fn weather_csv<'a>(
+    rows: impl IntoIterator<Item = (i64, Option<&'a SoleData>)>,
+    units: TemperatureUnit,
+) -> String {
+    let mut out = String::from(CSV_HEADER);
+    out.push('\n');
+
+    for (sol, data) in rows {
+        match data {
+            Some(data) => {
+                let _ = writeln!(
+                    out,
+                    "{},{},{},{},{},{},{}",
+                    sol,
+                    data.terrestrial_date.format("%Y-%m-%d"),
+                    data.min_temp
+                        .map(|temp| units.convert(temp).to_string())
+                        .unwrap_or_default(),
+                    data.max_temp
+                        .map(|temp| units.convert(temp).to_string())
+                        .unwrap_or_default(),
+                    data.sunrise
+                        .map(|time| time.to_string())
+                        .unwrap_or_default(),
+                    data.sunset.map(|time| time.to_string()).unwrap_or_default(),
+                    data.min_temp
+                        .zip(data.max_temp)
+                        .map(|(min, max)| units.convert_mean(min, max).to_string())
+                        .unwrap_or_default()
+                );
+            }
+            None => {
+                let _ = writeln!(out, "{sol},,,,,,");
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weather_csv() {
+        let data = SoleData {
+            min_temp: Some(-40),
+            max_temp: None,
+            ..SoleData::fixture(4804)
+        };
+        let converted = SoleData {
+            min_temp: Some(-40),
+            max_temp: Some(-7),
+            ..SoleData::fixture(4803)
+        };
+
+        let csv = weather_csv(
+            [(4803, Some(&converted)), (4804, Some(&data)), (4805, None)],
+            TemperatureUnit::Fahrenheit,
+        );
+
+        assert_eq!(
+            csv,
+            "sol,terrestrial_date,min_temp,max_temp,sunrise,sunset,mean_temp\n\
+             4803,2026-02-10,-40,19.4,05:19:00,17:22:00,-10.3\n\
+             4804,2026-02-10,-40,,05:19:00,17:22:00,\n\
+             4805,,,,,,\n"
+        );
+    }
+}
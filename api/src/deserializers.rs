@@ -1,10 +1,36 @@
+/// Parses an optional integer given either as a JSON string or number. Empty and unparseable values become `None`.
 pub fn i64_from_string<'de, D>(deserializer: D) -> Result<Option<i64>, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
     use serde::Deserialize;
-    let s = String::deserialize(deserializer)?;
-    Ok(s.parse::<i64>().ok())
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(match value {
+        serde_json::Value::String(s) => s.trim().parse::<i64>().ok(),
+        serde_json::Value::Number(n) => n.as_i64(),
+        _ => None,
+    })
+}
+
+/// Parses an optional numeric string. Missing values and NASA's `--` placeholder become `None`.
+pub fn f64_from_string<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::Deserialize;
+    let s = Option::<String>::deserialize(deserializer)?;
+    Ok(s.and_then(|s| s.trim().parse::<f64>().ok()))
+}
+
+/// Parses an optional string. Empty values and NASA's `--` placeholder become `None`.
+pub fn string_from_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    use serde::Deserialize;
+    let s = Option::<String>::deserialize(deserializer)?;
+    Ok(s.map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty() && s != "--"))
 }
 
 pub fn sole_from_string<'de, D>(deserializer: D) -> Result<crate::Sole, D::Error>
@@ -13,9 +39,14 @@ where
 {
     use serde::Deserialize;
     let s = String::deserialize(deserializer)?;
-    Ok(crate::Sole(
-        s.parse::<i64>().map_err(serde::de::Error::custom)?,
-    ))
+    let sol = s.parse::<i64>().map_err(serde::de::Error::custom)?;
+    // Landing is sol 0, so only negative sols are invalid
+    if sol < 0 {
+        return Err(serde::de::Error::custom(format!(
+            "invalid sol {sol}, expected a non-negative number"
+        )));
+    }
+    Ok(crate::Sole(sol))
 }
 
 pub fn naivedate_from_string<'de, D>(deserializer: D) -> Result<chrono::NaiveDate, D::Error>
@@ -25,15 +56,97 @@ where
     use serde::Deserialize;
     let s = String::deserialize(deserializer)?;
 
-    Ok(chrono::NaiveDate::parse_from_str(&s, "%Y-%m-%d").map_err(serde::de::Error::custom)?)
+    chrono::NaiveDate::parse_from_str(&s, "%Y-%m-%d").map_err(serde::de::Error::custom)
 }
 
-pub fn naivetime_from_string<'de, D>(deserializer: D) -> Result<chrono::NaiveTime, D::Error>
+/// Parses an optional time as %H:%M:%S or %H:%M. Empty values, `null` and NASA's `--` placeholder become `None`,
+/// as do missing fields with `#[serde(default)]`, while other unparseable times fail.
+pub fn naivetime_from_string<'de, D>(deserializer: D) -> Result<Option<chrono::NaiveTime>, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
     use serde::Deserialize;
-    let s = String::deserialize(deserializer)?;
+    let Some(s) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    let s = s.trim();
+    if s.is_empty() || s == "--" {
+        return Ok(None);
+    }
+
+    chrono::NaiveTime::parse_from_str(s, "%H:%M:%S")
+        .or_else(|_| chrono::NaiveTime::parse_from_str(s, "%H:%M"))
+        .map(Some)
+        .map_err(|err| {
+            serde::de::Error::custom(format!(
+                "invalid time '{s}', expected %H:%M:%S or %H:%M: {err}"
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveTime;
+    use serde_json::Value;
+
+    use super::*;
+
+    #[test]
+    fn test_naivetime_from_string() {
+        assert_eq!(
+            naivetime_from_string(Value::from("05:19:42")).unwrap(),
+            NaiveTime::from_hms_opt(5, 19, 42)
+        );
+        assert_eq!(
+            naivetime_from_string(Value::from("17:22")).unwrap(),
+            NaiveTime::from_hms_opt(17, 22, 0)
+        );
+
+        assert_eq!(naivetime_from_string(Value::from("")).unwrap(), None);
+        assert_eq!(naivetime_from_string(Value::from(" -- ")).unwrap(), None);
+        assert_eq!(naivetime_from_string(Value::Null).unwrap(), None);
+
+        let err = naivetime_from_string(Value::from("5pm")).unwrap_err();
+        assert!(err.to_string().contains("%H:%M:%S or %H:%M"));
+    }
+
+    #[test]
+    fn test_sole_from_string() {
+        assert_eq!(
+            sole_from_string(Value::from("4804")).unwrap(),
+            crate::Sole(4804)
+        );
+
+        assert_eq!(sole_from_string(Value::from("0")).unwrap(), crate::Sole(0));
+        let err = sole_from_string(Value::from("-5")).unwrap_err();
+        assert!(err.to_string().contains("invalid sol -5"));
+    }
+
+    #[test]
+    fn test_i64_from_string() {
+        assert_eq!(i64_from_string(Value::from("-74")).unwrap(), Some(-74));
+        assert_eq!(i64_from_string(Value::from(-74)).unwrap(), Some(-74));
+        assert_eq!(i64_from_string(Value::from("")).unwrap(), None);
+        assert_eq!(i64_from_string(Value::from("--")).unwrap(), None);
+        assert_eq!(i64_from_string(Value::Null).unwrap(), None);
+    }
+
+    #[test]
+    fn test_f64_from_string() {
+        assert_eq!(f64_from_string(Value::from("750")).unwrap(), Some(750.0));
+        assert_eq!(f64_from_string(Value::from("748.5")).unwrap(), Some(748.5));
+        assert_eq!(f64_from_string(Value::from("--")).unwrap(), None);
+        assert_eq!(f64_from_string(Value::Null).unwrap(), None);
+    }
 
-    Ok(chrono::NaiveTime::parse_from_str(&s, "%H:%M").map_err(serde::de::Error::custom)?)
+    #[test]
+    fn test_string_from_string() {
+        assert_eq!(
+            string_from_string(Value::from("Sunny")).unwrap(),
+            Some("Sunny".to_string())
+        );
+        assert_eq!(string_from_string(Value::from("--")).unwrap(), None);
+        assert_eq!(string_from_string(Value::from("")).unwrap(), None);
+        assert_eq!(string_from_string(Value::Null).unwrap(), None);
+    }
 }
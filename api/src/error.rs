@@ -0,0 +1,142 @@
+use std::fmt;
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use crate::{error_response, validation::DateError};
+
+/// Errors handlers respond with. Each variant maps to a status code and a stable `error` code of [`ErrorBody`].
+///
+/// [`ErrorBody`]: crate::response::ErrorBody
+#[derive(Debug, PartialEq, Eq)]
+pub enum ApiError {
+    MissingDate(String),
+    InvalidDate(String),
+    DateInFuture,
+    DateBeforeLanding,
+    InvalidWeek(String),
+    /// `from` is after `to`
+    InvalidRange(String),
+    RangeTooLarge(String),
+    InvalidSol(String),
+    /// Both a date and a sol were sent where only one is allowed
+    ConflictingParameters(String),
+    InvalidUnit(String),
+    InvalidFormat(String),
+    UnknownRover(String),
+    UnknownField(String),
+    /// Number of sols to list is out of range
+    InvalidCount(String),
+    /// Response would contain more sols than `LINX_MAX_SOLS_PER_RESPONSE`
+    TooManySols(String),
+    InvalidRadius(String),
+    InvalidBody(String),
+    /// Batch has more dates than allowed, answered with `413 Payload Too Large`
+    BatchTooLarge(String),
+    /// No weather data for what was requested
+    NotFound(String),
+    /// NASA could not be fetched
+    Upstream(String),
+}
+
+impl ApiError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Upstream(_) => StatusCode::BAD_GATEWAY,
+            ApiError::BatchTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            _ => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    /// Error code used in error responses
+    pub fn code(&self) -> &'static str {
+        match self {
+            ApiError::MissingDate(_) => "MISSING_DATE",
+            ApiError::InvalidDate(_) => "INVALID_DATE_FORMAT",
+            ApiError::DateInFuture => "DATE_IN_FUTURE",
+            ApiError::DateBeforeLanding => "DATE_BEFORE_LANDING",
+            ApiError::InvalidWeek(_) => "INVALID_WEEK",
+            ApiError::InvalidRange(_) => "INVALID_RANGE",
+            ApiError::RangeTooLarge(_) => "RANGE_TOO_LARGE",
+            ApiError::InvalidSol(_) => "INVALID_SOL",
+            ApiError::ConflictingParameters(_) => "CONFLICTING_PARAMETERS",
+            ApiError::InvalidUnit(_) => "INVALID_UNIT",
+            ApiError::InvalidFormat(_) => "INVALID_FORMAT",
+            ApiError::UnknownRover(_) => "UNKNOWN_ROVER",
+            ApiError::UnknownField(_) => "UNKNOWN_FIELD",
+            ApiError::InvalidCount(_) => "INVALID_N",
+            ApiError::TooManySols(_) => "TOO_MANY_SOLS",
+            ApiError::InvalidRadius(_) => "INVALID_RADIUS",
+            ApiError::InvalidBody(_) => "INVALID_BODY",
+            ApiError::BatchTooLarge(_) => "BATCH_TOO_LARGE",
+            ApiError::NotFound(_) => "NO_DATA",
+            ApiError::Upstream(_) => "UPSTREAM_UNAVAILABLE",
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::DateInFuture => DateError::InFuture.fmt(f),
+            ApiError::DateBeforeLanding => DateError::BeforeLanding.fmt(f),
+            ApiError::MissingDate(message)
+            | ApiError::InvalidDate(message)
+            | ApiError::InvalidWeek(message)
+            | ApiError::InvalidRange(message)
+            | ApiError::RangeTooLarge(message)
+            | ApiError::InvalidSol(message)
+            | ApiError::ConflictingParameters(message)
+            | ApiError::InvalidUnit(message)
+            | ApiError::InvalidFormat(message)
+            | ApiError::UnknownRover(message)
+            | ApiError::UnknownField(message)
+            | ApiError::InvalidCount(message)
+            | ApiError::TooManySols(message)
+            | ApiError::InvalidRadius(message)
+            | ApiError::InvalidBody(message)
+            | ApiError::BatchTooLarge(message)
+            | ApiError::NotFound(message)
+            | ApiError::Upstream(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl From<DateError> for ApiError {
+    fn from(err: DateError) -> Self {
+        match err {
+            DateError::InvalidFormat(message) => ApiError::InvalidDate(message),
+            DateError::InFuture => ApiError::DateInFuture,
+            DateError::BeforeLanding => ApiError::DateBeforeLanding,
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        error_response(self.status(), self.code(), self.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_error_from_date_error() {
+        for err in [
+            DateError::InvalidFormat("Invalid date.".to_string()),
+            DateError::InFuture,
+            DateError::BeforeLanding,
+        ] {
+            let (code, message) = (err.code(), err.to_string());
+            let api_error = ApiError::from(err);
+            assert_eq!(api_error.status(), StatusCode::BAD_REQUEST);
+            assert_eq!(api_error.code(), code);
+            assert_eq!(api_error.to_string(), message);
+        }
+    }
+}
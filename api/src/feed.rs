@@ -0,0 +1,234 @@
+use std::time::Duration;
+
+#[cfg(not(feature = "mock-data"))]
+use anyhow::anyhow;
+#[cfg(not(feature = "mock-data"))]
+use axum::http::{StatusCode, header};
+#[cfg(not(feature = "mock-data"))]
+use chrono::{DateTime, Utc};
+
+#[cfg(feature = "mock-data")]
+use crate::insight::is_insight_category;
+
+// Longest Retry-After honored, so a misbehaving upstream cannot stall fetches indefinitely
+#[cfg(not(feature = "mock-data"))]
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(5 * 60);
+
+/// `ETag` and `Last-Modified` of the last successful response of a feed, sent back with the next fetch
+/// so NASA can respond with `304 Not Modified` instead of the whole feed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeedValidators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+/// Body of a feed, or nothing when it has not changed since the validators sent with the fetch
+#[derive(Debug)]
+pub enum FeedBody {
+    Modified {
+        body: String,
+        validators: FeedValidators,
+    },
+    NotModified,
+}
+
+/// Fetches the body of the NASA weather feed of the category, conditionally on `validators` when known
+#[cfg(not(feature = "mock-data"))]
+pub async fn fetch_feed_body(
+    client: &reqwest::Client,
+    feed_url: &str,
+    category: &str,
+    validators: &FeedValidators,
+) -> anyhow::Result<FeedBody> {
+    let url = reqwest::Url::parse_with_params(
+        feed_url,
+        &[
+            ("feed", "weather"),
+            ("feedtype", "json"),
+            ("ver", "1.0"),
+            ("category", category),
+        ],
+    )?;
+    let mut request = client.get(url);
+    if let Some(etag) = &validators.etag {
+        request = request.header(header::IF_NONE_MATCH, etag);
+    }
+    if let Some(last_modified) = &validators.last_modified {
+        request = request.header(header::IF_MODIFIED_SINCE, last_modified);
+    }
+    let res = request.send().await?;
+
+    if res.status() == StatusCode::NOT_MODIFIED {
+        return Ok(FeedBody::NotModified);
+    }
+
+    if matches!(
+        res.status(),
+        StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+    ) {
+        let retry_after = res
+            .headers()
+            .get(header::RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| parse_retry_after(value, Utc::now()));
+        return Err(UpstreamBusy {
+            status: res.status(),
+            retry_after,
+        }
+        .into());
+    }
+
+    let header_value = |name| {
+        res.headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string)
+    };
+    let validators = FeedValidators {
+        etag: header_value(header::ETAG),
+        last_modified: header_value(header::LAST_MODIFIED),
+    };
+
+    // Body is read as text so it can be included in errors when NASA changes the payload
+    match res.text().await {
+        Ok(body) => Ok(FeedBody::Modified { body, validators }),
+        Err(err) => {
+            tracing::error!("Failed to fetch soles data: {}", err);
+            Err(anyhow!(err))
+        }
+    }
+}
+
+// Bundled fixtures never change, so they share a single ETag
+#[cfg(feature = "mock-data")]
+const FIXTURE_ETAG: &str = "\"fixture\"";
+
+/// Serves the bundled fixture of the category instead of fetching from NASA, so the server runs offline.
+/// Fetches with the validators of an earlier one find it unchanged.
+#[cfg(feature = "mock-data")]
+pub async fn fetch_feed_body(
+    _client: &reqwest::Client,
+    _feed_url: &str,
+    category: &str,
+    validators: &FeedValidators,
+) -> anyhow::Result<FeedBody> {
+    if validators.etag.as_deref() == Some(FIXTURE_ETAG) {
+        return Ok(FeedBody::NotModified);
+    }
+    tracing::info!("Serving bundled {category} fixture since the mock-data feature is enabled");
+    let fixture = if is_insight_category(category) {
+        include_str!("../fixtures/insight.json")
+    } else {
+        include_str!("../fixtures/msl.json")
+    };
+    Ok(FeedBody::Modified {
+        body: fixture.to_string(),
+        validators: FeedValidators {
+            etag: Some(FIXTURE_ETAG.to_string()),
+            last_modified: None,
+        },
+    })
+}
+
+/// How long NASA asked to wait before retrying a failed fetch, if it did
+#[cfg(not(feature = "mock-data"))]
+pub fn retry_after(err: &anyhow::Error) -> Option<Duration> {
+    err.downcast_ref::<UpstreamBusy>()
+        .and_then(|busy| busy.retry_after)
+}
+
+/// The bundled fixtures never ask to wait
+#[cfg(feature = "mock-data")]
+pub fn retry_after(_err: &anyhow::Error) -> Option<Duration> {
+    None
+}
+
+/// NASA responded with `429 Too Many Requests` or `503 Service Unavailable`
+#[cfg(not(feature = "mock-data"))]
+#[derive(Debug)]
+struct UpstreamBusy {
+    status: StatusCode,
+    /// How long NASA asked to wait with `Retry-After`, if it did
+    retry_after: Option<Duration>,
+}
+
+#[cfg(not(feature = "mock-data"))]
+impl std::fmt::Display for UpstreamBusy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "NASA responded with {}", self.status)?;
+        if let Some(retry_after) = self.retry_after {
+            write!(f, " and asked to retry after {retry_after:?}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(not(feature = "mock-data"))]
+impl std::error::Error for UpstreamBusy {}
+
+/// Parses a `Retry-After` value, either seconds or an HTTP date, into how long to wait from `now`.
+/// Dates in the past wait for nothing and waits are capped at [`MAX_RETRY_AFTER`].
+#[cfg(not(feature = "mock-data"))]
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    let delay = match value.parse::<u64>() {
+        Ok(secs) => Duration::from_secs(secs),
+        Err(_) => {
+            let date = DateTime::parse_from_rfc2822(value).ok()?;
+            (date.to_utc() - now).to_std().unwrap_or_default()
+        }
+    };
+    Some(delay.min(MAX_RETRY_AFTER))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(feature = "mock-data"))]
+    #[test]
+    fn test_parse_retry_after() {
+        let now = DateTime::parse_from_rfc3339("2026-02-10T12:00:00Z")
+            .unwrap()
+            .to_utc();
+        assert_eq!(
+            parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        assert_eq!(
+            parse_retry_after("Tue, 10 Feb 2026 12:00:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Tue, 10 Feb 2026 11:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("86400", now), Some(MAX_RETRY_AFTER));
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[cfg(feature = "mock-data")]
+    #[tokio::test]
+    async fn test_fetch_feed_body_serves_fixtures() {
+        let client = reqwest::Client::new();
+        for category in ["msl", "insight_temperature"] {
+            let body = fetch_feed_body(
+                &client,
+                "http://127.0.0.1:1/",
+                category,
+                &FeedValidators::default(),
+            )
+            .await
+            .unwrap();
+            let FeedBody::Modified { body, validators } = body else {
+                panic!("Fixture was not served");
+            };
+            assert!(serde_json::from_str::<serde_json::Value>(&body).is_ok());
+
+            let body = fetch_feed_body(&client, "http://127.0.0.1:1/", category, &validators)
+                .await
+                .unwrap();
+            assert!(matches!(body, FeedBody::NotModified));
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::{collections::VecDeque, sync::Mutex};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Payload fetched from NASA, kept to diagnose upstream changes over time
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RawHistoryEntry {
+    pub rover: String,
+    pub fetched_at: DateTime<Utc>,
+    /// Number of sols parsed from the payload
+    pub sol_count: usize,
+    #[schema(value_type = Object)]
+    pub payload: serde_json::Value,
+}
+
+/// Ring buffer of the latest payloads fetched from NASA, dropping the oldest once full
+#[derive(Debug)]
+pub struct RawHistory {
+    capacity: usize,
+    entries: Mutex<VecDeque<RawHistoryEntry>>,
+}
+
+impl RawHistory {
+    /// Creates a history keeping up to `capacity` payloads. A capacity of 0 disables the history.
+    pub fn new(capacity: usize) -> Self {
+        RawHistory {
+            capacity,
+            entries: Mutex::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    pub fn push(&self, entry: RawHistoryEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap_or_else(|err| err.into_inner());
+        while entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Returns payloads fetched for the rover, newest first
+    pub fn entries(&self, rover: &str) -> Vec<RawHistoryEntry> {
+        let entries = self.entries.lock().unwrap_or_else(|err| err.into_inner());
+        entries
+            .iter()
+            .rev()
+            .filter(|entry| entry.rover == rover)
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(rover: &str, sol_count: usize) -> RawHistoryEntry {
+        RawHistoryEntry {
+            rover: rover.to_string(),
+            fetched_at: Utc::now(),
+            sol_count,
+            payload: serde_json::Value::Null,
+        }
+    }
+
+    #[test]
+    fn test_raw_history_drops_oldest() {
+        let history = RawHistory::new(2);
+        history.push(entry("msl", 1));
+        history.push(entry("insight", 2));
+        history.push(entry("msl", 3));
+
+        let sol_counts = |rover| {
+            history
+                .entries(rover)
+                .iter()
+                .map(|entry| entry.sol_count)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(sol_counts("msl"), vec![3]);
+        assert_eq!(sol_counts("insight"), vec![2]);
+    }
+
+    #[test]
+    fn test_raw_history_disabled() {
+        let history = RawHistory::new(0);
+        history.push(entry("msl", 1));
+        assert!(history.entries("msl").is_empty());
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::{Sole, SoleData, truncate_for_log};
+
+/// Whether the feed category is InSight's, e.g. `insight_temperature`, whose payload differs from the rover feeds
+pub fn is_insight_category(category: &str) -> bool {
+    category.starts_with("insight")
+}
+
+/// Payload of the InSight weather feed. Each sol is an object keyed by its number, listed in `sol_keys`.
+#[derive(Debug, Deserialize)]
+pub struct NasaInSightData {
+    sol_keys: Vec<String>,
+    #[serde(flatten)]
+    sols: HashMap<String, serde_json::Value>,
+}
+
+/// Weather of a single sol as summarized by InSight's sensors
+#[derive(Debug, Deserialize)]
+struct InSightSol {
+    /// Atmospheric temperature in Celsius
+    #[serde(rename = "AT")]
+    temperature: Option<SensorSummary>,
+    /// Atmospheric pressure in Pa
+    #[serde(rename = "PRE")]
+    pressure: Option<SensorSummary>,
+    /// Time of the first sample of the sol
+    #[serde(rename = "First_UTC")]
+    first_utc: DateTime<Utc>,
+    #[serde(rename = "Month_ordinal")]
+    month_ordinal: Option<u32>,
+}
+
+/// Average, lowest and highest sample of a sensor over a sol
+#[derive(Debug, Deserialize)]
+struct SensorSummary {
+    av: f64,
+    mn: f64,
+    mx: f64,
+}
+
+/// Normalizes InSight sols into soles data, skipping and logging sols that fail to parse.
+/// Returns the number of sols listed in the feed along with the parsed ones.
+pub fn parse_insight_sols(data: &NasaInSightData) -> (usize, Vec<SoleData>) {
+    let soles: Vec<SoleData> = data
+        .sol_keys
+        .iter()
+        .filter_map(|key| match parse_insight_sol(key, data.sols.get(key)) {
+            Ok(data) => Some(data),
+            Err(err) => {
+                tracing::warn!("Skipping InSight sol {key}. {err}");
+                None
+            }
+        })
+        .collect();
+
+    let skipped = data.sol_keys.len() - soles.len();
+    if skipped > 0 {
+        tracing::warn!("Skipped {skipped} of {} InSight sols", data.sol_keys.len());
+    }
+    (data.sol_keys.len(), soles)
+}
+
+fn parse_insight_sol(key: &str, value: Option<&serde_json::Value>) -> anyhow::Result<SoleData> {
+    let sol = key
+        .parse::<i64>()
+        .ok()
+        .filter(|sol| *sol >= 0)
+        .ok_or_else(|| anyhow::anyhow!("Invalid sol key, expected a non-negative number"))?;
+    let value = value.ok_or_else(|| anyhow::anyhow!("Sol is listed in sol_keys but missing"))?;
+    let insight_sol = serde_path_to_error::deserialize::<_, InSightSol>(value).map_err(|err| {
+        anyhow::anyhow!(
+            "Failed to parse {}: {}. Sol: {}",
+            err.path(),
+            err.inner(),
+            truncate_for_log(&value.to_string())
+        )
+    })?;
+
+    // NASA reports whole degrees for the rover feeds, so InSight's are rounded to match
+    Ok(SoleData {
+        id: key.to_string(),
+        terrestrial_date: insight_sol.first_utc.date_naive(),
+        sol: Sole(sol),
+        min_temp: insight_sol
+            .temperature
+            .as_ref()
+            .map(|temperature| temperature.mn.round() as i64),
+        max_temp: insight_sol
+            .temperature
+            .as_ref()
+            .map(|temperature| temperature.mx.round() as i64),
+        pressure: insight_sol.pressure.map(|pressure| pressure.av.round()),
+        atmo_opacity: None,
+        season: insight_sol
+            .month_ordinal
+            .map(|month| format!("Month {month}")),
+        sunrise: None,
+        sunset: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    #[test]
+    fn test_parse_insight_sols() {
+        let data: NasaInSightData = serde_json::from_str(
+            r#"{
+                "675": {
+                    "AT": {"av": -62.314, "ct": 177556, "mn": -96.872, "mx": -15.908},
+                    "First_UTC": "2020-10-19T18:32:20Z",
+                    "Last_UTC": "2020-10-20T19:11:55Z",
+                    "Month_ordinal": 10,
+                    "PRE": {"av": 743.67, "ct": 887400, "mn": 722.0, "mx": 768.34},
+                    "Season": "fall",
+                    "WD": {"most_common": null}
+                },
+                "676": {
+                    "First_UTC": "2020-10-20T19:11:55Z",
+                    "PRE": {"av": 744.1, "ct": 887400, "mn": 723.0, "mx": 769.0}
+                },
+                "677": {"First_UTC": "yesterday"},
+                "sol_keys": ["675", "676", "677", "678"],
+                "validity_checks": {"sols_checked": ["675", "676", "677"]}
+            }"#,
+        )
+        .unwrap();
+
+        let (no_of_sols, soles) = parse_insight_sols(&data);
+
+        assert_eq!(no_of_sols, 4);
+        assert_eq!(soles.len(), 2);
+        let sol = &soles[0];
+        assert_eq!(sol.sol, Sole(675));
+        assert_eq!(
+            sol.terrestrial_date,
+            NaiveDate::from_ymd_opt(2020, 10, 19).unwrap()
+        );
+        assert_eq!((sol.min_temp, sol.max_temp), (Some(-97), Some(-16)));
+        assert_eq!(sol.pressure, Some(744.0));
+        assert_eq!(sol.season.as_deref(), Some("Month 10"));
+        assert_eq!((sol.sunrise, sol.sunset), (None, None));
+
+        // Sols without temperature sensor data are kept
+        assert_eq!(soles[1].sol, Sole(676));
+        assert_eq!(soles[1].min_temp, None);
+    }
+
+    #[test]
+    fn test_is_insight_category() {
+        assert!(is_insight_category("insight_temperature"));
+        assert!(!is_insight_category("msl"));
+    }
+}
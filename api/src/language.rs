@@ -0,0 +1,199 @@
+use axum::{
+    extract::Request,
+    http::{HeaderValue, header},
+    middleware::Next,
+    response::Response,
+};
+
+/// Language of error messages, chosen with `Accept-Language`. Error codes are never translated.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    /// Language of the messages in code, used when no supported language is accepted
+    #[default]
+    English,
+    /// Norwegian Bokmål
+    Norwegian,
+}
+
+impl Language {
+    /// Tag sent in `Content-Language`
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Norwegian => "nb",
+        }
+    }
+
+    /// Matches on the primary subtag, so e.g. `en-GB` is English. `no` is taken to mean Bokmål.
+    fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.split('-').next().unwrap_or_default();
+        match primary.to_ascii_lowercase().as_str() {
+            "en" => Some(Language::English),
+            "nb" | "no" => Some(Language::Norwegian),
+            _ => None,
+        }
+    }
+}
+
+tokio::task_local! {
+    static LANGUAGE: Language;
+}
+
+/// Language of the request being handled, English when called outside the language middleware
+pub fn current() -> Language {
+    LANGUAGE.try_with(|language| *language).unwrap_or_default()
+}
+
+/// Middleware that makes [`current`] return the language picked from the `Accept-Language` header of the request
+pub async fn accept_language(request: Request, next: Next) -> Response {
+    let language = request
+        .headers()
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .map(negotiate_language)
+        .unwrap_or_default();
+    LANGUAGE.scope(language, next.run(request)).await
+}
+
+/// Picks the supported language with the highest quality in an `Accept-Language` value, the first one on ties.
+/// Falls back to English when none is supported.
+fn negotiate_language(accept_language: &str) -> Language {
+    let mut best: Option<(Language, f32)> = None;
+    for range in accept_language.split(',') {
+        let mut parts = range.split(';').map(str::trim);
+        let Some(language) = parts.next().and_then(Language::from_tag) else {
+            continue;
+        };
+        let quality = parts
+            .find_map(|param| param.strip_prefix("q="))
+            .map_or(Some(1.0), |quality| quality.parse::<f32>().ok())
+            .unwrap_or(0.0);
+        if quality > 0.0 && best.is_none_or(|(_, best_quality)| quality > best_quality) {
+            best = Some((language, quality));
+        }
+    }
+    best.map(|(language, _)| language).unwrap_or_default()
+}
+
+/// Translation of an English error message into the language. Keyed by the whole message, so messages with
+/// route-specific content, e.g. the rejected value or the allowed query parameters of a route, only get a
+/// translation written for them. `None` when there is none, so the English message is kept.
+pub fn translate(message: &str, language: Language) -> Option<&'static str> {
+    match language {
+        Language::English => None,
+        Language::Norwegian => NORWEGIAN
+            .iter()
+            .find(|(english, _)| *english == message)
+            .map(|(_, translated)| *translated),
+    }
+}
+
+/// Sets `Content-Language` to the language of the error message. Adds `Vary: Accept-Language`, since the message
+/// depends on the header.
+pub fn with_content_language(mut response: Response, language: Language) -> Response {
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_LANGUAGE,
+        HeaderValue::from_static(language.tag()),
+    );
+    headers.append(header::VARY, HeaderValue::from_static("accept-language"));
+    response
+}
+
+/// Norwegian translations of error messages of weather routes, keyed by the English message. Every entry is checked
+/// against the message handlers build in `test_error_messages_have_norwegian_translations`.
+const NORWEGIAN: &[(&str, &str)] = &[
+    (
+        "Send request with query parameter ?date=<requested date>.",
+        "Send forespørselen med parameteren ?date=<dato>.",
+    ),
+    (
+        "Send request with query parameter ?date=<date> or ?sol=<sol>.",
+        "Send forespørselen med parameteren ?date=<dato> eller ?sol=<sol>.",
+    ),
+    (
+        "Send request with query parameters ?date1=<first date>&date2=<second date>.",
+        "Send forespørselen med parameterne ?date1=<første dato>&date2=<andre dato>.",
+    ),
+    (
+        "Invalid format for date. Allowed formats are %Y-%m-%d, %Y/%m/%d, %d-%m-%Y, rfc3339, %Y-%m-%dT%H:%M:%S in UTC and Unix timestamps in seconds.",
+        "Ugyldig dato. Tillatte formater er %Y-%m-%d, %Y/%m/%d, %d-%m-%Y, rfc3339, %Y-%m-%dT%H:%M:%S i UTC og Unix-tidsstempler i sekunder.",
+    ),
+    (
+        "Invalid date. Date must be at most 64 characters long.",
+        "Ugyldig dato. Datoen kan være på høyst 64 tegn.",
+    ),
+    (
+        "Date is in the future. No weather data exists yet.",
+        "Datoen er i fremtiden. Det finnes ingen værdata for den ennå.",
+    ),
+    (
+        "Date is before Curiosity landed on 2012-08-06 05:17:00 UTC. No weather data exists.",
+        "Datoen er før Curiosity landet 2012-08-06 05:17:00 UTC. Det finnes ingen værdata.",
+    ),
+    (
+        "Invalid unit. Allowed units are c, f and k.",
+        "Ugyldig enhet. Tillatte enheter er c, f og k.",
+    ),
+    (
+        "Invalid format. Allowed formats are json, csv and xml.",
+        "Ugyldig format. Tillatte formater er json, csv og xml.",
+    ),
+    (
+        "Invalid range. from must not be after to.",
+        "Ugyldig periode. from kan ikke være etter to.",
+    ),
+    (
+        "No weather data found for the requested sol.",
+        "Fant ingen værdata for forespurt sol.",
+    ),
+    (
+        "NASA did not report both sunrise and sunset for the requested sol.",
+        "NASA rapporterte ikke både soloppgang og solnedgang for forespurt sol.",
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate_language() {
+        assert_eq!(negotiate_language("nb-NO"), Language::Norwegian);
+        assert_eq!(negotiate_language("no"), Language::Norwegian);
+        assert_eq!(negotiate_language("en-US,en;q=0.9"), Language::English);
+        assert_eq!(
+            negotiate_language("en;q=0.5, nb;q=0.8"),
+            Language::Norwegian
+        );
+        assert_eq!(negotiate_language("fr, nb;q=0.3"), Language::Norwegian);
+        assert_eq!(negotiate_language("nb;q=0, en;q=0.1"), Language::English);
+        assert_eq!(negotiate_language("fr-FR, de"), Language::English);
+        assert_eq!(negotiate_language("*"), Language::English);
+    }
+
+    #[test]
+    fn test_translate() {
+        assert_eq!(
+            translate(
+                "Date is in the future. No weather data exists yet.",
+                Language::Norwegian
+            ),
+            Some("Datoen er i fremtiden. Det finnes ingen værdata for den ennå.")
+        );
+        assert_eq!(
+            translate(
+                "Date is in the future. No weather data exists yet.",
+                Language::English
+            ),
+            None
+        );
+        assert_eq!(
+            translate(
+                "Unknown rover 'curiosity'. Available rovers are msl.",
+                Language::Norwegian
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,4491 @@
+mod admin;
+mod anomalies;
+mod compact;
+mod conditional;
+mod config;
+mod conversion;
+mod cors;
+mod csv;
+mod deserializers;
+mod error;
+mod feed;
+mod history;
+mod insight;
+mod language;
+mod logging;
+mod metrics;
+mod negotiation;
+mod openapi;
+mod pretty;
+mod ratelimit;
+mod request_id;
+mod response;
+mod storage;
+mod strict;
+mod units;
+mod validation;
+mod watchdog;
+
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    net::SocketAddr,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, anyhow};
+use axum::{
+    Json, Router,
+    body::Bytes,
+    extract::{ConnectInfo, Path, Request, State, rejection::JsonRejection},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode, Uri, header},
+    middleware::{self, Next},
+    response::{Html, IntoResponse, Response},
+    routing::{get, post},
+};
+use axum_server::tls_rustls::RustlsConfig;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime};
+use quick_xml::escape::escape;
+use serde::{Deserialize, Serialize};
+use tokio_util::sync::CancellationToken;
+use tower_http::compression::CompressionLayer;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::anomalies::validate_soles;
+use crate::compact::explicit_nulls;
+use crate::conditional::{IfModifiedSince, IfNoneMatch, etag_for, with_etag, with_last_modified};
+use crate::config::Config;
+use crate::conversion::{
+    ConversionInfo, SOL_DRIFT_SECS, calculate_no_of_martian_sol_elapsed, earth_date_for_sol,
+    sol_span,
+};
+use crate::cors::cors_layer;
+use crate::csv::weather_csv;
+use crate::deserializers::{
+    f64_from_string, i64_from_string, naivedate_from_string, naivetime_from_string,
+    sole_from_string, string_from_string,
+};
+use crate::error::ApiError;
+use crate::feed::{FeedBody, FeedValidators, fetch_feed_body, retry_after};
+use crate::history::{RawHistory, RawHistoryEntry};
+use crate::insight::{NasaInSightData, is_insight_category, parse_insight_sols};
+use crate::language::{Language, with_content_language};
+use crate::metrics::Metrics;
+use crate::negotiation::{ResponseFormat, negotiate, prefers_json};
+use crate::openapi::RouteSummary;
+use crate::ratelimit::RateLimiter;
+use crate::response::{
+    DaylightResponse, ErrorBody, KelvinWeatherResponse, MARS_TIME_LABEL, SolOutOfRangeBody,
+    WeatherBatchEntry, WeatherBatchResult, WeatherCompareResponse, WeatherExtremesResponse,
+    WeatherFields, WeatherRangeEntry, WeatherResponse, WeatherStatsResponse, WeatherWindowResponse,
+    weather_range_xml, weather_xml,
+};
+use crate::strict::StrictQuery;
+pub use crate::units::TemperatureUnit;
+use crate::validation::{DateError, validate_date};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Hash, Clone)]
+pub struct Sole(i64);
+
+impl From<i64> for Sole {
+    fn from(value: i64) -> Self {
+        Sole(value)
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct SoleData {
+    id: String,
+
+    #[serde(deserialize_with = "naivedate_from_string")]
+    terrestrial_date: NaiveDate,
+
+    #[serde(deserialize_with = "sole_from_string")]
+    sol: Sole,
+
+    #[serde(deserialize_with = "i64_from_string")]
+    min_temp: Option<i64>,
+    #[serde(deserialize_with = "i64_from_string")]
+    max_temp: Option<i64>,
+
+    #[serde(default, deserialize_with = "f64_from_string")]
+    pressure: Option<f64>,
+    #[serde(default, deserialize_with = "string_from_string")]
+    atmo_opacity: Option<String>,
+
+    #[serde(default, deserialize_with = "string_from_string")]
+    season: Option<String>,
+
+    /// Missing for feeds that do not report sunrise and sunset, such as InSight, and for sols NASA left them out of
+    #[serde(default, deserialize_with = "naivetime_from_string")]
+    sunrise: Option<NaiveTime>,
+    #[serde(default, deserialize_with = "naivetime_from_string")]
+    sunset: Option<NaiveTime>,
+}
+
+impl SoleData {
+    /// Whether both the lowest and highest temperature were recorded
+    fn has_temps(&self) -> bool {
+        self.min_temp.is_some() && self.max_temp.is_some()
+    }
+}
+
+#[cfg(test)]
+impl SoleData {
+    /// Sole data with typical values for tests
+    fn fixture(sol: i64) -> Self {
+        SoleData {
+            id: sol.to_string(),
+            terrestrial_date: NaiveDate::from_ymd_opt(2026, 2, 10).unwrap(),
+            sol: Sole(sol),
+            min_temp: Some(-74),
+            max_temp: Some(-7),
+            pressure: Some(750.0),
+            atmo_opacity: Some("Sunny".to_string()),
+            season: Some("Month 4".to_string()),
+            sunrise: NaiveTime::from_hms_opt(5, 19, 0),
+            sunset: NaiveTime::from_hms_opt(17, 22, 0),
+        }
+    }
+}
+
+/// Soles data keyed by rover and sol, since sols are only unique per rover
+pub type SolesData = HashMap<(String, Sole), SoleData>;
+
+/// Immutable view of cached soles data along with when it was updated. Handlers doing several lookups take one
+/// snapshot so every lookup sees the same data, even when the updater replaces it in between.
+#[derive(Debug, Clone)]
+struct SolesSnapshot {
+    updated_at: chrono::DateTime<chrono::Utc>,
+    data: SolesData,
+}
+
+impl SolesSnapshot {
+    fn get(&self, rover: &str, sol: impl Into<Sole>) -> Option<&SoleData> {
+        self.data.get(&(rover.to_string(), sol.into()))
+    }
+
+    /// Returns the earliest and latest sol of the rover, or `None` when it has none
+    fn sol_bounds(&self, rover: &str) -> Option<(i64, i64)> {
+        let mut sols = self
+            .data
+            .keys()
+            .filter(|(data_rover, _)| data_rover == rover)
+            .map(|(_, sol)| sol.0);
+        let first = sols.next()?;
+        Some(sols.fold((first, first), |(min, max), sol| {
+            (min.min(sol), max.max(sol))
+        }))
+    }
+
+    /// Aggregates every sol of the rover, or `None` when it has none
+    fn stats(&self, rover: &str, units: TemperatureUnit) -> Option<WeatherStatsResponse> {
+        WeatherStatsResponse::new(
+            self.data
+                .iter()
+                .filter(|((data_rover, _), _)| data_rover == rover)
+                .map(|(_, data)| data),
+            units,
+        )
+    }
+
+    /// Takes the `n` coldest and warmest sols of the rover
+    fn extremes(&self, rover: &str, n: usize, units: TemperatureUnit) -> WeatherExtremesResponse {
+        WeatherExtremesResponse::new(
+            self.data
+                .iter()
+                .filter(|((data_rover, _), _)| data_rover == rover)
+                .map(|(_, data)| data),
+            n,
+            units,
+        )
+    }
+}
+
+struct InnerCachedSolesData {
+    /// Replaced on update, or copied first when snapshots of it are still in use
+    snapshot: Arc<SolesSnapshot>,
+    /// Payloads as returned by NASA by rover. Missing for rovers loaded from the database.
+    raw: HashMap<String, serde_json::Value>,
+    /// Number of implausible rows in the latest fetch by rover
+    anomalies: HashMap<String, usize>,
+    /// Validators of the latest fetch by rover, for fetching only when the feed has changed
+    validators: HashMap<String, FeedValidators>,
+    /// When NASA was last checked for new data, set on updates and when the feed has not changed.
+    /// Unlike `updated_at` of the snapshot it moves on without new data.
+    checked_at: chrono::DateTime<chrono::Utc>,
+    /// Serialized JSON weather responses, filled on first request and cleared on every update.
+    /// Readers fill it while holding the read lock so it never outlives the data it was built from.
+    weather_json: std::sync::Mutex<HashMap<(String, Sole, TemperatureUnit), Bytes>>,
+}
+
+pub struct CachedSolesData(tokio::sync::RwLock<InnerCachedSolesData>);
+
+// Time a refresh may take past the refresh interval before cached data is considered stale. Covers the worst case
+// of a fetch: every retry timing out, the backoff between them and a `Retry-After` wait.
+const REFRESH_GRACE: Duration = Duration::from_secs(30 * 60);
+
+/// Whether NASA has not been checked since `checked_at` for longer than a refresh may take, including one that is
+/// still in progress. Decides both the `stale` flag of /health and the `Warning` header of weather responses.
+fn is_stale(
+    checked_at: chrono::DateTime<chrono::Utc>,
+    refresh_interval: Duration,
+    now: chrono::DateTime<chrono::Utc>,
+) -> bool {
+    is_older_than(checked_at, refresh_interval + REFRESH_GRACE, now)
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct CacheStatus {
+    updated_at: chrono::DateTime<chrono::Utc>,
+    /// When NASA was last checked for new data, which may be later than `updated_at` when the feed had not changed
+    checked_at: chrono::DateTime<chrono::Utc>,
+    soles_count: usize,
+    stale: bool,
+    /// Number of implausible rows in the latest fetches, kept or dropped depending on `LINX_DROP_ANOMALIES`
+    anomalies: usize,
+}
+
+/// Freshness of cached data and outcomes of background refreshes
+#[derive(Debug, Serialize, ToSchema)]
+struct HealthStatus {
+    #[serde(flatten)]
+    cache: CacheStatus,
+    /// Refreshes of a rover's soles data by the background updater or /admin/refresh since startup that succeeded
+    refreshes_succeeded: u64,
+    /// Refreshes of a rover's soles data by the background updater or /admin/refresh since startup that failed.
+    /// Growing while `refreshes_succeeded` does not means the cache is going stale.
+    refreshes_failed: u64,
+    /// Restarts of the background updater by the watchdog after it panicked or exited since startup
+    updater_restarts: u64,
+    /// When the watchdog last restarted the background updater, `null` when it has not
+    last_updater_restart: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl CachedSolesData {
+    pub fn new(
+        data: SolesData,
+        raw: HashMap<String, serde_json::Value>,
+        anomalies: HashMap<String, usize>,
+    ) -> Self {
+        let now = chrono::Utc::now();
+        CachedSolesData(tokio::sync::RwLock::new(InnerCachedSolesData {
+            snapshot: Arc::new(SolesSnapshot {
+                updated_at: now,
+                data,
+            }),
+            raw,
+            anomalies,
+            validators: HashMap::new(),
+            checked_at: now,
+            weather_json: Default::default(),
+        }))
+    }
+
+    pub async fn get_data_for_sol(&self, rover: &str, sol: impl Into<Sole>) -> Option<SoleData> {
+        self.0.read().await.snapshot.get(rover, sol).cloned()
+    }
+
+    /// Returns the cached data as of now, taking the lock once
+    pub(crate) async fn snapshot(&self) -> Arc<SolesSnapshot> {
+        self.0.read().await.snapshot.clone()
+    }
+
+    /// Returns the JSON weather response for the sol of the rover, serializing it only once per update
+    pub async fn weather_json(
+        &self,
+        rover: &str,
+        sol: impl Into<Sole>,
+        units: TemperatureUnit,
+    ) -> serde_json::Result<Option<Bytes>> {
+        let key = (rover.to_string(), sol.into(), units);
+        let inner = self.0.read().await;
+        let mut memo = inner
+            .weather_json
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(json) = memo.get(&key) {
+            return Ok(Some(json.clone()));
+        }
+
+        let Some(data) = inner.snapshot.get(&key.0, key.1.clone()) else {
+            return Ok(None);
+        };
+        let json = Bytes::from(serde_json::to_vec(&WeatherResponse::new(data, units))?);
+        memo.insert(key, json.clone());
+        Ok(Some(json))
+    }
+
+    /// Returns sol and terrestrial date of every cached sol of the rover, sorted ascending by sol
+    pub async fn sols(&self, rover: &str) -> Vec<(Sole, NaiveDate)> {
+        let mut sols: Vec<(Sole, NaiveDate)> = self
+            .0
+            .read()
+            .await
+            .snapshot
+            .data
+            .iter()
+            .filter(|((data_rover, _), _)| data_rover == rover)
+            .map(|(_, data)| (data.sol.clone(), data.terrestrial_date))
+            .collect();
+        sols.sort();
+        sols
+    }
+
+    /// Returns data for every cached sol of the rover within `radius` of `sol`, sorted ascending by sol
+    pub async fn window(&self, rover: &str, sol: impl Into<Sole>, radius: i64) -> Vec<SoleData> {
+        let sol = sol.into();
+        let inner = self.0.read().await;
+        (sol.0 - radius..=sol.0 + radius)
+            .filter_map(|sol| inner.snapshot.get(rover, sol).cloned())
+            .collect()
+    }
+
+    /// Returns data for the cached sol of the rover closest to `sol`, preferring the earlier sol on ties
+    pub async fn nearest_sol(&self, rover: &str, sol: impl Into<Sole>) -> Option<SoleData> {
+        let sol = sol.into();
+        self.0
+            .read()
+            .await
+            .snapshot
+            .data
+            .iter()
+            .filter(|((data_rover, _), _)| data_rover == rover)
+            .map(|(_, data)| data)
+            .min_by_key(|data| ((data.sol.0 - sol.0).abs(), data.sol.0))
+            .cloned()
+    }
+
+    /// Returns data for the most recent cached sol of the rover
+    pub async fn latest(&self, rover: &str) -> Option<SoleData> {
+        self.0
+            .read()
+            .await
+            .snapshot
+            .data
+            .iter()
+            .filter(|((data_rover, _), _)| data_rover == rover)
+            .max_by_key(|((_, sol), _)| sol.0)
+            .map(|(_, data)| data.clone())
+    }
+
+    pub async fn raw(&self, rover: &str) -> Option<serde_json::Value> {
+        self.0.read().await.raw.get(rover).cloned()
+    }
+
+    pub async fn updated_at(&self) -> chrono::DateTime<chrono::Utc> {
+        self.0.read().await.snapshot.updated_at
+    }
+
+    pub async fn checked_at(&self) -> chrono::DateTime<chrono::Utc> {
+        self.0.read().await.checked_at
+    }
+
+    /// Returns validators of the latest fetch of every rover fetched from NASA
+    pub async fn validators(&self) -> HashMap<String, FeedValidators> {
+        self.0.read().await.validators.clone()
+    }
+
+    /// Status of the cache, which is stale when NASA has not been checked successfully for a while
+    pub(crate) async fn status(&self, refresh_interval: Duration) -> CacheStatus {
+        let inner = self.0.read().await;
+
+        CacheStatus {
+            updated_at: inner.snapshot.updated_at,
+            checked_at: inner.checked_at,
+            soles_count: inner.snapshot.data.len(),
+            stale: is_stale(inner.checked_at, refresh_interval, chrono::Utc::now()),
+            anomalies: inner.anomalies.values().sum(),
+        }
+    }
+
+    /// Replaces cached soles data of the rover, keeping data of other rovers.
+    /// Data and `updated_at` are set under a single write lock so readers never see new data with an old timestamp.
+    pub(crate) async fn update(&self, rover: &str, fetched: FetchedSoles) {
+        let mut inner = self.0.write().await;
+        let snapshot = Arc::make_mut(&mut inner.snapshot);
+        snapshot
+            .data
+            .retain(|(data_rover, _), _| data_rover != rover);
+        snapshot.data.extend(fetched.soles);
+        snapshot.updated_at = chrono::Utc::now();
+        inner.checked_at = snapshot.updated_at;
+        inner.raw.insert(rover.to_string(), fetched.raw);
+        inner.anomalies.insert(rover.to_string(), fetched.anomalies);
+        inner
+            .validators
+            .insert(rover.to_string(), fetched.validators);
+        inner
+            .weather_json
+            .get_mut()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clear();
+    }
+
+    /// Records that NASA was checked and its feed has not changed, keeping cached data and `updated_at`
+    pub async fn mark_checked(&self) {
+        self.0.write().await.checked_at = chrono::Utc::now();
+    }
+}
+
+struct SharedState {
+    config: Config,
+    http_client: reqwest::Client,
+    cached_soles_data: CachedSolesData,
+    metrics: Metrics,
+    rate_limiter: RateLimiter,
+    raw_history: RawHistory,
+}
+
+/// Serves the API until shut down, as configured by the environment
+pub async fn run() -> anyhow::Result<()> {
+    logging::init();
+
+    let config = Config::from_env()?;
+    tracing::info!(
+        "Using feed {} with categories {}",
+        config.feed_url,
+        config.feed_categories.join(", ")
+    );
+    tracing::info!("Refreshing soles data every {:?}", config.refresh_interval);
+    tracing::info!("Timing out NASA fetches after {:?}", config.fetch_timeout);
+    if config.rate_limit_per_minute > 0 {
+        tracing::info!(
+            "Limiting weather requests to {} per minute per client",
+            config.rate_limit_per_minute
+        );
+    } else {
+        tracing::info!("Rate limiting is disabled");
+    }
+
+    // Shared by all fetches so connections are reused and every request is bounded by the timeout
+    let http_client = reqwest::Client::builder()
+        .connect_timeout(config.fetch_timeout)
+        .timeout(config.fetch_timeout)
+        .build()
+        .context("Unable to build HTTP client")?;
+
+    let metrics = Metrics::default();
+    let raw_history = RawHistory::new(config.raw_history_size);
+    let InitialSolesData {
+        soles_data,
+        raw,
+        anomalies,
+    } = loop {
+        match load_initial_soles_data(&http_client, &config, &metrics, &raw_history).await {
+            Ok(initial) => break initial,
+            Err(err) if config.wait_for_data => {
+                tracing::warn!(
+                    "{err:#}. Trying again in {:?} since LINX_WAIT_FOR_DATA is set",
+                    WAIT_FOR_DATA_DELAY
+                );
+                tokio::time::sleep(WAIT_FOR_DATA_DELAY).await;
+            }
+            Err(err) => return Err(err),
+        }
+    };
+
+    let shared_state = Arc::new(SharedState {
+        rate_limiter: RateLimiter::new(config.rate_limit_per_minute),
+        config,
+        http_client,
+        cached_soles_data: CachedSolesData::new(soles_data, raw, anomalies),
+        metrics,
+        raw_history,
+    });
+
+    let now = chrono::Utc::now();
+    for rover in &shared_state.config.feed_categories {
+        log_newest_sol(&shared_state.cached_soles_data, rover, now).await;
+    }
+
+    // Cancelled on shutdown signal to let the server and updater finish their work
+    let shutdown = CancellationToken::new();
+
+    // Starts background task that updates cached data, unless disabled to keep the cache as loaded at startup.
+    // The watchdog respawns it when it panics or exits before shutdown.
+    let updater_handle = if shared_state.config.disable_updater {
+        tracing::warn!("Background updater is disabled. Soles data will not be refreshed");
+        None
+    } else {
+        let state = shared_state.clone();
+        let shutdown = shutdown.clone();
+        Some(tokio::spawn(async move {
+            watchdog::supervise(
+                "updater",
+                || run_updater(state.clone(), shutdown.clone()),
+                watchdog::MAX_RESTARTS,
+                watchdog::RESTART_DELAY,
+                || state.metrics.record_updater_restart(),
+                shutdown.clone(),
+            )
+            .await
+        }))
+    };
+
+    let bind_addr = shared_state.config.bind_addr;
+    let tls = shared_state.config.tls.clone();
+    let app = build_app(shared_state);
+
+    // Loaded before binding so invalid certificates fail startup
+    let tls_config = match tls {
+        Some(tls) => Some(
+            RustlsConfig::from_pem_file(&tls.cert, &tls.key)
+                .await
+                .with_context(|| {
+                    format!(
+                        "Unable to load TLS certificate {} and key {}",
+                        tls.cert.display(),
+                        tls.key.display()
+                    )
+                })?,
+        ),
+        None => None,
+    };
+
+    // run our app with hyper, listening on configured address
+    tracing::info!("Binding to {bind_addr}");
+    let listener = tokio::net::TcpListener::bind(bind_addr)
+        .await
+        .with_context(|| format!("Unable to bind to {bind_addr}"))?;
+    tracing::info!(
+        "Listening on {} with {}",
+        listener.local_addr()?,
+        if tls_config.is_some() {
+            "HTTPS"
+        } else {
+            "HTTP"
+        }
+    );
+
+    let server_shutdown = shutdown.clone();
+    let mut server_handle = tokio::spawn(async move {
+        tracing::info!("Starting server...");
+        match tls_config {
+            Some(tls_config) => serve_tls(listener, app, tls_config, server_shutdown).await,
+            None => axum::serve(
+                listener,
+                app.into_make_service_with_connect_info::<SocketAddr>(),
+            )
+            .with_graceful_shutdown(server_shutdown.cancelled_owned())
+            .await
+            .context("Server error"),
+        }
+    });
+
+    tokio::select! {
+        _ = shutdown_signal() => tracing::info!("Shutting down server..."),
+        result = &mut server_handle => {
+            shutdown.cancel();
+            if let Some(updater_handle) = updater_handle {
+                updater_handle.await.context("Updater task failed")?;
+            }
+            return result.context("Server task failed")?;
+        }
+    }
+
+    // Lets in-flight requests finish before exiting
+    shutdown.cancel();
+    server_handle.await.context("Server task failed")??;
+    if let Some(updater_handle) = updater_handle {
+        updater_handle.await.context("Updater task failed")?;
+    }
+    tracing::info!("Server shut down");
+
+    Ok(())
+}
+
+// Newest sol older than this at startup suggests NASA has stopped publishing new data
+const UPSTREAM_STALE_AFTER: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Logs the newest cached sol of the rover so operators can confirm the data looks current
+async fn log_newest_sol(
+    cached_soles_data: &CachedSolesData,
+    rover: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) {
+    let Some(newest) = cached_soles_data.latest(rover).await else {
+        tracing::warn!("No soles data available for rover {rover}");
+        return;
+    };
+
+    let newest_date = newest
+        .terrestrial_date
+        .and_time(NaiveTime::default())
+        .and_utc();
+    if is_older_than(newest_date, UPSTREAM_STALE_AFTER, now) {
+        tracing::warn!(
+            "Newest sol of rover {rover} is {} from {}, more than {} days ago. Upstream data may be stale",
+            newest.sol.0,
+            newest.terrestrial_date,
+            UPSTREAM_STALE_AFTER.as_secs() / (24 * 60 * 60)
+        );
+    } else {
+        tracing::info!(
+            "Newest sol of rover {rover} is {} from {}",
+            newest.sol.0,
+            newest.terrestrial_date
+        );
+    }
+}
+
+/// Builds the application with all routes and layers, without binding or starting background tasks
+fn build_app(state: Arc<SharedState>) -> Router {
+    let cors = cors_layer(&state.config.cors_origins);
+
+    // Routes registered with `get` also answer HEAD with the same status and headers but no body
+    Router::new()
+        .route(
+            "/weather",
+            get(weather).layer(middleware::map_response(negotiation::vary_accept)),
+        )
+        .route(
+            "/weather/sol/{sol}",
+            get(weather_by_sol).layer(middleware::map_response(negotiation::vary_accept)),
+        )
+        .route("/weather/kelvin", get(weather_kelvin))
+        .route("/weather/window", get(weather_window))
+        .route(
+            "/weather/latest",
+            get(weather_latest).layer(middleware::map_response(negotiation::vary_accept)),
+        )
+        .route("/weather/daylight", get(weather_daylight))
+        .route("/weather/compare", get(weather_compare))
+        .route("/weather/stats", get(weather_stats))
+        .route("/weather/extremes", get(weather_extremes))
+        .route("/weather/batch", post(weather_batch))
+        .route_layer(middleware::from_fn(reject_earth_tz))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            data_freshness_headers,
+        ))
+        // Inside the metrics so rate limited requests are counted too
+        .route_layer(middleware::from_fn_with_state(state.clone(), rate_limit))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            track_weather_metrics,
+        ))
+        .route("/", get(hello))
+        .route("/sols", get(sols))
+        .route("/sol/now", get(current_sol))
+        .route("/convert", get(convert))
+        .route("/about/conversion", get(about_conversion))
+        .route("/health", get(health))
+        .route("/metrics", get(prometheus_metrics))
+        .route("/raw", get(raw_payload))
+        .route("/raw/history", get(raw_history))
+        .route("/admin/refresh", post(admin_refresh))
+        .route("/openapi.json", get(openapi::openapi_json))
+        .route("/docs", get(openapi::docs))
+        .fallback(not_found)
+        .layer(middleware::from_fn(compact::compact))
+        .layer(middleware::from_fn(language::accept_language))
+        .layer(middleware::from_fn(pretty::pretty_json))
+        .layer(middleware::from_fn(request_id::request_id))
+        .layer(cors)
+        .layer(CompressionLayer::new())
+        .with_state(state)
+}
+
+/// Updates cached data once every refresh interval until shutdown
+async fn run_updater(state: Arc<SharedState>, shutdown: CancellationToken) {
+    loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => break,
+            _ = tokio::time::sleep(state.config.refresh_interval) => {}
+        }
+        tracing::info!("Updating soles data...");
+        let config = &state.config;
+        let validators = state.cached_soles_data.validators().await;
+        let results = tokio::select! {
+            _ = shutdown.cancelled() => break,
+            results = fetch_rovers_soles_data(
+                &state.http_client,
+                config,
+                &validators,
+                FETCH_RETRIES,
+                FETCH_BASE_DELAY,
+            ) => results,
+        };
+        apply_fetch_results(&state, results).await;
+    }
+    tracing::info!("Stopped updating soles data");
+}
+
+/// Updates cached data with the fetched soles data of every rover, keeping cached data of rovers that failed.
+/// Returns the rovers that failed.
+async fn apply_fetch_results(
+    state: &SharedState,
+    results: Vec<(String, anyhow::Result<FetchOutcome>)>,
+) -> Vec<String> {
+    let mut failed_rovers = Vec::new();
+    for (rover, result) in results {
+        state.metrics.record_upstream_fetch(&result);
+        match result {
+            Ok(FetchOutcome::Modified(fetched)) => {
+                state.raw_history.push(fetched.history_entry(&rover));
+                if let Some(db_path) = &state.config.db_path {
+                    persist_soles_data(db_path.clone(), fetched.soles.clone()).await;
+                }
+                state.cached_soles_data.update(&rover, fetched).await;
+                state.metrics.record_refresh_success();
+                tracing::info!("Updated soles data for rover {rover}!");
+            }
+            Ok(FetchOutcome::NotModified) => {
+                state.cached_soles_data.mark_checked().await;
+                state.metrics.record_refresh_success();
+                tracing::info!("Soles data for rover {rover} has not changed");
+            }
+            Err(err) => {
+                state.metrics.record_refresh_failure();
+                tracing::error!(
+                    "Unable to fetch soles data for rover {rover}. Trying again in {:?}. Err: {err}",
+                    state.config.refresh_interval
+                );
+                failed_rovers.push(rover);
+            }
+        }
+    }
+    failed_rovers
+}
+
+/// Soles data the server starts with
+struct InitialSolesData {
+    soles_data: SolesData,
+    raw: HashMap<String, serde_json::Value>,
+    anomalies: HashMap<String, usize>,
+}
+
+/// Fetches soles data for every rover, filling in rovers that failed from the database when configured.
+/// Fails when no rover has any data, so the server never starts with an always empty cache.
+async fn load_initial_soles_data(
+    http_client: &reqwest::Client,
+    config: &Config,
+    metrics: &Metrics,
+    raw_history: &RawHistory,
+) -> anyhow::Result<InitialSolesData> {
+    let mut soles_data = SolesData::new();
+    let mut raw = HashMap::new();
+    let mut anomalies = HashMap::new();
+    let mut failed_rovers = Vec::new();
+    // Fetched unconditionally since there is nothing cached to compare with
+    let results = fetch_rovers_soles_data(
+        http_client,
+        config,
+        &HashMap::new(),
+        FETCH_RETRIES,
+        FETCH_BASE_DELAY,
+    )
+    .await;
+    for (rover, result) in results {
+        metrics.record_upstream_fetch(&result);
+        let result = result.and_then(|outcome| {
+            outcome.modified().ok_or_else(|| {
+                anyhow!("NASA responded with 304 Not Modified to an unconditional fetch")
+            })
+        });
+        match result {
+            Ok(fetched) => {
+                raw_history.push(fetched.history_entry(&rover));
+                soles_data.extend(fetched.soles);
+                anomalies.insert(rover.clone(), fetched.anomalies);
+                raw.insert(rover, fetched.raw);
+            }
+            Err(err) => {
+                tracing::warn!("Unable to fetch soles data for rover {rover}. Err: {err}");
+                failed_rovers.push(rover);
+            }
+        }
+    }
+
+    if let Some(db_path) = &config.db_path {
+        if !soles_data.is_empty() {
+            persist_soles_data(db_path.clone(), soles_data.clone()).await;
+        }
+        if !failed_rovers.is_empty() {
+            tracing::warn!("Loading soles data for rovers {failed_rovers:?} from database");
+            let db_path = db_path.clone();
+            let stored = tokio::task::spawn_blocking(move || storage::load_soles(&db_path))
+                .await?
+                .context("Unable to load soles data from database")?;
+            let stored: SolesData = stored
+                .into_iter()
+                .filter(|((rover, _), _)| failed_rovers.contains(rover))
+                .collect();
+            tracing::info!("Loaded {} soles from database", stored.len());
+            soles_data.extend(stored);
+        }
+    }
+
+    if soles_data.is_empty() {
+        return Err(anyhow!(
+            "Unable to fetch soles data for rovers {failed_rovers:?} and no stored data is available"
+        ));
+    }
+
+    Ok(InitialSolesData {
+        soles_data,
+        raw,
+        anomalies,
+    })
+}
+
+/// Serves the app over HTTPS until `shutdown` is cancelled, letting in-flight requests finish
+async fn serve_tls(
+    listener: tokio::net::TcpListener,
+    app: Router,
+    tls_config: RustlsConfig,
+    shutdown: CancellationToken,
+) -> anyhow::Result<()> {
+    let handle = axum_server::Handle::new();
+    let shutdown_handle = handle.clone();
+    tokio::spawn(async move {
+        shutdown.cancelled().await;
+        shutdown_handle.graceful_shutdown(None);
+    });
+
+    axum_server::from_tcp_rustls(listener.into_std()?, tls_config)?
+        .handle(handle)
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+        .context("Server error")
+}
+
+/// Completes when the process receives Ctrl-C, or SIGTERM on Unix as sent by container orchestrators
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        match tokio::signal::ctrl_c().await {
+            Ok(_) => tracing::info!("Received Ctrl-C"),
+            Err(err) => {
+                tracing::error!("Unable to listen for Ctrl-C. Err: {err}");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+                tracing::info!("Received SIGTERM");
+            }
+            Err(err) => {
+                tracing::error!("Unable to listen for SIGTERM. Err: {err}");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+/// Description of the service served by / to clients accepting JSON
+#[derive(Debug, Serialize, ToSchema)]
+struct HelloResponse {
+    #[schema(example = "linx")]
+    name: String,
+    description: Option<String>,
+    #[schema(example = "0.1.0")]
+    version: &'static str,
+    /// Path of the OpenAPI spec
+    #[schema(example = "/openapi.json")]
+    openapi: &'static str,
+    /// Path of Swagger UI for the spec
+    #[schema(example = "/docs")]
+    docs: &'static str,
+    routes: Vec<RouteSummary>,
+}
+
+/// Handler that serves usage notes and lists every route
+#[utoipa::path(
+    get,
+    path = "/",
+    responses((status = 200, description = "Help text listing the routes, as JSON with `Accept: application/json`", content(
+        (String = "text/html"),
+        (HelloResponse = "application/json"),
+    )))
+)]
+async fn hello(headers: HeaderMap) -> Response {
+    // Caches must not serve the HTML page to JSON clients or the other way around
+    let vary = [(header::VARY, HeaderValue::from_static("accept"))];
+    if prefers_json(&headers) {
+        let info = openapi::ApiDoc::openapi().info;
+        let body = HelloResponse {
+            name: info.title,
+            description: info.description,
+            version: env!("CARGO_PKG_VERSION"),
+            openapi: "/openapi.json",
+            docs: "/docs",
+            routes: openapi::routes(),
+        };
+        return (vary, Json(body)).into_response();
+    }
+
+    let routes: String = openapi::routes()
+        .into_iter()
+        .map(|route| {
+            let summary = route
+                .summary
+                .map(|summary| format!(" - {}", escape(summary.as_str())))
+                .unwrap_or_default();
+            format!(
+                "\n            <li>{} {}{summary}</li>",
+                route.method,
+                escape(route.path.as_str())
+            )
+        })
+        .collect();
+
+    let html = Html(format!(
+        "
+        <h1>Hello!</h1>
+        {HELLO_USAGE}
+        <h2>Routes</h2>
+        <ul>{routes}
+        </ul>"
+    ));
+    (vary, html).into_response()
+}
+
+// Usage notes of the landing page. Routes are listed from the OpenAPI spec so they stay in sync.
+const HELLO_USAGE: &str = r"<section>
+            <p>Weather api is available as /weather.</p>
+            <p>Use /weather?date=[requested date].
+            <br/>
+            Valid formats for date are %Y-%m-%d (e.g. 2026-02-15), %Y/%m/%d (e.g. 2026/02/15), %d-%m-%Y (e.g. 15-02-2026), rfc3339 (e.g. 2026-02-15T21:42:00%2B01:00 or 2026-02-15T20:42:00Z), %Y-%m-%dT%H:%M:%S taken as UTC (e.g. 2026-02-15T20:42:00) or a Unix timestamp in seconds (e.g. 1771188120).
+            </p>
+            <p>Use /weather?date=[requested date]&units=[c|f|k] to get temperatures in Celsius (default), Fahrenheit or Kelvin. Converted temperatures are rounded to one decimal place.</p>
+            <p>Use /weather?date=[requested date]&format=csv or send header Accept: text/csv to get weather data as CSV.</p>
+            <p>Use /weather?date=[requested date]&format=xml or send header Accept: application/xml to get weather data as XML.</p>
+            <p>Use /weather?date=[requested date]&fields=[comma-separated fields] (e.g. fields=min_temp,max_temp) to get only some fields of JSON weather data.</p>
+            <p>Use /weather?date=[requested date]&nearest=true to get weather data for the closest available sol when the requested sol has no data.</p>
+            <p>Use /weather?from=[first date]&to=[last date] to get weather data for every sol in a range of up to 100 days.</p>
+            <p>Use /weather?week=[ISO week] (e.g. week=2026-W07) to get weather data for every sol from Monday to Sunday of a week.</p>
+            <p>Add require_temps=true to range and window requests to leave out sols without both temperatures.</p>
+            <p>Use /weather/kelvin?date=[requested date] to get temperatures in Kelvin as numbers with one decimal place.</p>
+            <p>Use /weather?date=[requested date]&rover=[rover] to get weather data of another configured rover feed than the first one, also for /sols, /sol/now and /weather/kelvin.</p>
+            <p>Use /weather/window?date=[requested date]&radius=[no of sols] to get the lowest and highest temperatures of the sols around a date.</p>
+            <p>Use POST /weather/batch with a JSON array of up to 100 dates as body to get weather data for each of the dates.</p>
+            <p>Use /weather/compare?date1=[first date]&date2=[second date] to get weather data of two dates side by side with the change in min and max temperature from the first to the second.</p>
+            <p>Use /weather/daylight?date=[requested date] to get sunrise, sunset and the duration of daylight of the sol of a date. Sunrise and sunset are in Mars local mean solar time, as labeled by tz_label, so weather routes reject an earth_tz parameter.</p>
+            <p>Use /weather/latest to get weather data for the most recent sol with available data.</p>
+            <p>Use /weather/stats to get the number of sols, the sols and dates covered and the coldest and warmest recorded temperatures.</p>
+            <p>Use /weather/extremes?n=5 to get the n coldest and warmest sols, at most 50.</p>
+            <p>Use /weather/sol/[martian sol] (e.g. /weather/sol/4804) to get weather data for a Martian sol directly.</p>
+            <p>Use /sols?limit=[max no of sols] to list the Martian sols with available weather data.</p>
+            <p>Use /convert?date=[date] to convert a date to the Martian sol it falls on, or /convert?sol=[martian sol] to convert a sol to an Earth date, without weather data.</p>
+            <p>Use /about/conversion to see how dates are converted to sols.</p>
+            <p>Use /sol/now to get the current Martian sol and whether weather data for it is available.</p>
+            <p>Add pretty=true to any request to get indented JSON.</p>
+            <p>Add compact=false to weather requests to get null instead of N/A for missing values and every optional field, also when missing.</p>
+            <p>Send Accept-Language: nb to get error messages in Norwegian Bokmål.</p>
+            <p>Add strict=true to any request to get 422 Unprocessable Entity listing query parameters the route does not know, e.g. dat instead of date.</p>
+            <p>Cache freshness is available as /health.</p>
+            <p>Prometheus metrics are available as /metrics.</p>
+            <p>Latest payload as returned by NASA is available as /raw, and the last few payloads with when they were fetched as /raw/history.</p>
+            <p>OpenAPI spec is available as /openapi.json and can be browsed at /docs.</p>
+            <p>Send Accept: application/json to / to get this page as JSON describing the service and its routes.</p>
+        </section>";
+
+/// Fallback handler for unknown routes that lists the available ones
+async fn not_found(uri: Uri) -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(serde_json::json!({
+            "error": "NOT_FOUND",
+            "message": format!("No route for {}. See / or /docs for usage.", uri.path()),
+            "endpoints": openapi::endpoints(),
+            "request_id": request_id::current(),
+        })),
+    )
+        .into_response()
+}
+
+/// Outcome of a refresh requested with /admin/refresh
+#[derive(Debug, Serialize, ToSchema)]
+struct AdminRefreshResponse {
+    /// Number of cached sols of all rovers after the refresh
+    soles_count: usize,
+    updated_at: chrono::DateTime<chrono::Utc>,
+    /// Rovers that could not be fetched and keep their previously cached data
+    failed_rovers: Vec<String>,
+}
+
+/// Handler that refreshes cached soles data from NASA right away, e.g. after a known upstream correction.
+/// Requires `Authorization: Bearer <token>` with the token set in `LINX_ADMIN_TOKEN`.
+#[utoipa::path(
+    post,
+    path = "/admin/refresh",
+    responses(
+        (status = 200, description = "Cached data was refreshed for all rovers except `failed_rovers`", body = AdminRefreshResponse),
+        (status = 401, description = "Missing or invalid admin token", body = ErrorBody),
+        (status = 502, description = "No rover could be fetched from NASA", body = ErrorBody),
+    )
+)]
+async fn admin_refresh(State(state): State<Arc<SharedState>>, headers: HeaderMap) -> Response {
+    if !admin::is_authorized(&headers, state.config.admin_token.as_deref()) {
+        let mut response = error_response(
+            StatusCode::UNAUTHORIZED,
+            "UNAUTHORIZED",
+            "Missing or invalid admin token.".to_string(),
+        );
+        response
+            .headers_mut()
+            .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+        return response;
+    }
+
+    tracing::info!("Refreshing soles data as requested by an admin...");
+    // Fetched unconditionally and without retries, so corrections are always picked up and the caller gets a
+    // prompt answer it can retry itself
+    let results = fetch_rovers_soles_data(
+        &state.http_client,
+        &state.config,
+        &HashMap::new(),
+        0,
+        FETCH_BASE_DELAY,
+    )
+    .await;
+    let failed_rovers = apply_fetch_results(&state, results).await;
+    if failed_rovers.len() == state.config.feed_categories.len() {
+        return ApiError::Upstream(format!(
+            "Unable to fetch soles data for {}. Cached data is unchanged.",
+            failed_rovers.join(", ")
+        ))
+        .into_response();
+    }
+
+    let status = state
+        .cached_soles_data
+        .status(state.config.refresh_interval)
+        .await;
+    Json(AdminRefreshResponse {
+        soles_count: status.soles_count,
+        updated_at: status.updated_at,
+        failed_rovers,
+    })
+    .into_response()
+}
+
+/// Handler that reports freshness of cached soles data. Responds with 503 when data is stale.
+#[utoipa::path(
+    get,
+    path = "/health",
+    responses(
+        (status = 200, description = "Cached data is fresh", body = HealthStatus),
+        (status = 503, description = "Cached data is stale", body = HealthStatus),
+    )
+)]
+async fn health(State(state): State<Arc<SharedState>>) -> impl IntoResponse {
+    let status = state
+        .cached_soles_data
+        .status(state.config.refresh_interval)
+        .await;
+    let status_code = if status.stale {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else {
+        StatusCode::OK
+    };
+    let (refreshes_succeeded, refreshes_failed) = state.metrics.refreshes();
+    let (updater_restarts, last_updater_restart) = state.metrics.updater_restarts();
+
+    (
+        status_code,
+        Json(HealthStatus {
+            cache: status,
+            refreshes_succeeded,
+            refreshes_failed,
+            updater_restarts,
+            last_updater_restart,
+        }),
+    )
+}
+
+/// Handler that renders metrics in Prometheus text format
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    responses((status = 200, description = "Metrics in Prometheus text format", content_type = "text/plain", body = String))
+)]
+async fn prometheus_metrics(State(state): State<Arc<SharedState>>) -> impl IntoResponse {
+    let status = state
+        .cached_soles_data
+        .status(state.config.refresh_interval)
+        .await;
+    let cache_age = chrono::Utc::now() - status.updated_at;
+
+    (
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        state
+            .metrics
+            .render(status.soles_count, cache_age.num_seconds()),
+    )
+}
+
+/// Handler that describes how dates are converted to sols
+#[utoipa::path(
+    get,
+    path = "/about/conversion",
+    responses((status = 200, description = "Landing date, sol length and formula used to convert dates to sols", body = ConversionInfo))
+)]
+async fn about_conversion() -> Json<ConversionInfo> {
+    Json(ConversionInfo::new())
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct CurrentSolQuery {
+    /// Rover feed category, defaults to the first of `LINX_FEED_CATEGORY`
+    rover: Option<String>,
+}
+
+/// Handler that returns the Martian sol for the current time and whether weather data for it is cached
+#[utoipa::path(
+    get,
+    path = "/sol/now",
+    params(CurrentSolQuery),
+    responses(
+        (status = 200, description = "Current sol as `martian_sol_day`, the `timestamp` used and whether `data_available`"),
+        (status = 400, description = "Unknown rover", body = ErrorBody),
+    )
+)]
+async fn current_sol(
+    StrictQuery(params): StrictQuery<CurrentSolQuery>,
+    State(state): State<Arc<SharedState>>,
+) -> Response {
+    let rover = match requested_rover(params.rover.as_deref(), &state.config) {
+        Ok(rover) => rover,
+        Err(err) => return err.into_response(),
+    };
+
+    let now = chrono::Utc::now();
+    match calculate_no_of_martian_sol_elapsed(now) {
+        Ok(sol) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "martian_sol_day": sol.to_string(),
+                "timestamp": now,
+                "data_available": state
+                    .cached_soles_data
+                    .get_data_for_sol(rover, sol)
+                    .await
+                    .is_some(),
+            })),
+        )
+            .into_response(),
+        Err(err) => error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "CONVERSION_FAILED",
+            err.to_string(),
+        ),
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct ConvertQuery {
+    /// Date as %Y-%m-%d, %Y/%m/%d, %d-%m-%Y, rfc3339, %Y-%m-%dT%H:%M:%S in UTC or Unix timestamp in seconds
+    date: Option<String>,
+    /// Martian sol to convert to an Earth date instead of a date
+    sol: Option<String>,
+}
+
+/// Handler that converts a date to the Martian sol it falls on, or a sol to an Earth date, without looking up
+/// weather data
+#[utoipa::path(
+    get,
+    path = "/convert",
+    params(ConvertQuery),
+    responses(
+        (status = 200, description = "Sol as `martian_sol_day`, the normalized UTC `datetime` of the date or the last second of the sol, the approximate UTC `sol_start` and `sol_end` of the sol and how many `sol_drift_seconds` a sol is longer than an Earth day"),
+        (status = 400, description = "Missing, malformed or pre-landing date or sol, or both date and sol", body = ErrorBody),
+    )
+)]
+async fn convert(StrictQuery(params): StrictQuery<ConvertQuery>) -> Response {
+    let maybe_date = match (params.date, params.sol) {
+        (Some(maybe_date), None) => maybe_date,
+        (None, Some(maybe_sol)) => return convert_sol(&maybe_sol),
+        (Some(_), Some(_)) => {
+            return ApiError::ConflictingParameters(
+                "Send either query parameter ?date=<date> or ?sol=<sol>, not both.".to_string(),
+            )
+            .into_response();
+        }
+        (None, None) => {
+            return ApiError::MissingDate(
+                "Send request with query parameter ?date=<date> or ?sol=<sol>.".to_string(),
+            )
+            .into_response();
+        }
+    };
+
+    let datetime = match parse_date_from_string(&maybe_date) {
+        Ok(datetime) => datetime,
+        Err(err) => {
+            return ApiError::from(DateError::InvalidFormat(err.to_string())).into_response();
+        }
+    };
+
+    match calculate_no_of_martian_sol_elapsed(datetime) {
+        Ok(sol) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "martian_sol_day": sol.to_string(),
+                "datetime": datetime,
+                "sol_start": sol_span(sol).map(|(start, _)| start),
+                "sol_end": sol_span(sol).map(|(_, end)| end),
+                "sol_drift_seconds": SOL_DRIFT_SECS,
+            })),
+        )
+            .into_response(),
+        Err(_) => ApiError::DateBeforeLanding.into_response(),
+    }
+}
+
+/// Converts a sol to the last second in UTC that maps to it
+fn convert_sol(maybe_sol: &str) -> Response {
+    let Ok(sol) = maybe_sol.parse::<i64>() else {
+        return ApiError::InvalidSol("Invalid sol. Sol must be a whole number.".to_string())
+            .into_response();
+    };
+
+    match earth_date_for_sol(sol) {
+        Ok(datetime) => (
+            StatusCode::OK,
+            Json(serde_json::json!({
+                "martian_sol_day": sol.to_string(),
+                "datetime": datetime,
+                "sol_start": sol_span(sol).map(|(start, _)| start),
+                "sol_end": sol_span(sol).map(|(_, end)| end),
+                "sol_drift_seconds": SOL_DRIFT_SECS,
+            })),
+        )
+            .into_response(),
+        Err(err) => ApiError::InvalidSol(err.to_string()).into_response(),
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct RawQuery {
+    /// Rover feed category, defaults to the first of `LINX_FEED_CATEGORY`
+    rover: Option<String>,
+}
+
+/// Handler that returns the latest payload as returned by NASA, for debugging upstream data issues
+#[utoipa::path(
+    get,
+    path = "/raw",
+    params(RawQuery),
+    responses(
+        (status = 200, description = "Payload as returned by NASA", body = Object),
+        (status = 400, description = "Unknown rover", body = ErrorBody),
+        (status = 404, description = "Data was loaded from the database", body = ErrorBody),
+    )
+)]
+async fn raw_payload(
+    StrictQuery(params): StrictQuery<RawQuery>,
+    State(state): State<Arc<SharedState>>,
+) -> Response {
+    let rover = match requested_rover(params.rover.as_deref(), &state.config) {
+        Ok(rover) => rover,
+        Err(err) => return err.into_response(),
+    };
+
+    match state.cached_soles_data.raw(rover).await {
+        Some(raw) => (StatusCode::OK, Json(raw)).into_response(),
+        None => error_response(
+            StatusCode::NOT_FOUND,
+            "NO_RAW_DATA",
+            "Soles data was loaded from the database and no payload from NASA is available yet."
+                .to_string(),
+        ),
+    }
+}
+
+/// Handler that returns the last payloads fetched from NASA with when they were fetched, newest first.
+/// Helps tell when the upstream payload changed.
+#[utoipa::path(
+    get,
+    path = "/raw/history",
+    params(RawQuery),
+    responses(
+        (status = 200, description = "Payloads as returned by NASA, newest first", body = Vec<RawHistoryEntry>),
+        (status = 400, description = "Unknown rover", body = ErrorBody),
+    )
+)]
+async fn raw_history(
+    StrictQuery(params): StrictQuery<RawQuery>,
+    State(state): State<Arc<SharedState>>,
+) -> Response {
+    match requested_rover(params.rover.as_deref(), &state.config) {
+        Ok(rover) => (StatusCode::OK, Json(state.raw_history.entries(rover))).into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Warning sent with successful weather responses served from data not checked within the refresh interval
+const STALE_WARNING: &str = "110 linx \"Response is stale\"";
+
+const X_DATA_UPDATED_AT: HeaderName = HeaderName::from_static("x-data-updated-at");
+
+/// Number of sols left out of range and window responses by `require_temps`
+const X_FILTERED_SOLS: HeaderName = HeaderName::from_static("x-filtered-sols");
+
+/// Middleware that tells clients how fresh cached data is. Adds `X-Data-Updated-At` with the RFC 3339 time of the
+/// last update to every response, `Cache-Control` with the seconds until the next refresh to successful and
+/// `304 Not Modified` responses, and a `Warning` header to successful responses when the cache is stale as reported
+/// by /health.
+async fn data_freshness_headers(
+    State(state): State<Arc<SharedState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // Read before handling so the header never claims fresher data than the response was built from
+    let updated_at = state.cached_soles_data.updated_at().await;
+    let checked_at = state.cached_soles_data.checked_at().await;
+    let mut response = next.run(request).await;
+
+    if let Ok(value) = HeaderValue::from_str(&updated_at.to_rfc3339()) {
+        response.headers_mut().insert(X_DATA_UPDATED_AT, value);
+    }
+    let now = chrono::Utc::now();
+    if response.status().is_success() || response.status() == StatusCode::NOT_MODIFIED {
+        let max_age = secs_until_refresh(checked_at, state.config.refresh_interval, now);
+        if let Ok(value) = HeaderValue::from_str(&format!("max-age={max_age}")) {
+            response.headers_mut().insert(header::CACHE_CONTROL, value);
+        }
+    }
+    if response.status().is_success() && is_stale(checked_at, state.config.refresh_interval, now) {
+        response
+            .headers_mut()
+            .insert(header::WARNING, HeaderValue::from_static(STALE_WARNING));
+    }
+    response
+}
+
+/// Whole seconds until data checked at `checked_at` is due to be refreshed, or 0 when the refresh is overdue
+fn secs_until_refresh(
+    checked_at: chrono::DateTime<chrono::Utc>,
+    refresh_interval: Duration,
+    now: chrono::DateTime<chrono::Utc>,
+) -> u64 {
+    let age = (now - checked_at).to_std().unwrap_or_default();
+    refresh_interval.saturating_sub(age).as_secs()
+}
+
+/// Whether data updated at `updated_at` has not been updated within `max_age`, e.g. because refreshes keep failing
+fn is_older_than(
+    updated_at: chrono::DateTime<chrono::Utc>,
+    max_age: Duration,
+    now: chrono::DateTime<chrono::Utc>,
+) -> bool {
+    (now - updated_at).to_std().is_ok_and(|age| age > max_age)
+}
+
+#[derive(Debug, Deserialize)]
+struct EarthTzQuery {
+    earth_tz: Option<String>,
+}
+
+/// Middleware that rejects requests for sunrise and sunset in an Earth time zone with `400 Bad Request`,
+/// since they are Martian times that no Earth time zone applies to
+async fn reject_earth_tz(request: Request, next: Next) -> Response {
+    let earth_tz = axum::extract::Query::<EarthTzQuery>::try_from_uri(request.uri())
+        .ok()
+        .and_then(|query| query.0.earth_tz);
+    match earth_tz {
+        Some(earth_tz) => error_response(
+            StatusCode::BAD_REQUEST,
+            "EARTH_TZ_UNSUPPORTED",
+            format!(
+                "Sunrise and sunset are in {MARS_TIME_LABEL} at the rover's location and cannot be converted to the Earth time zone {earth_tz:?}. \
+                 Use sol_start and sol_end for when the sol starts and ends in UTC."
+            ),
+        ),
+        None => next.run(request).await,
+    }
+}
+
+/// Middleware that counts requests and response statuses of weather routes
+async fn track_weather_metrics(
+    State(state): State<Arc<SharedState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let response = next.run(request).await;
+    state.metrics.record_weather_response(response.status());
+    response
+}
+
+/// Middleware that rejects requests with `429 Too Many Requests` when the client IP exceeds its rate limit
+async fn rate_limit(
+    State(state): State<Arc<SharedState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let client = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+
+    if let Some(client) = client
+        && let Err(retry_after) = state.rate_limiter.check(client, Instant::now())
+    {
+        let retry_after_secs = retry_after.as_secs_f64().ceil() as u64;
+        let mut response = error_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            "RATE_LIMITED",
+            format!("Too many requests. Try again in {retry_after_secs} seconds."),
+        );
+        response
+            .headers_mut()
+            .insert(header::RETRY_AFTER, retry_after_secs.into());
+        return response;
+    }
+
+    next.run(request).await
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct SolsQuery {
+    /// Maximum number of sols to list
+    limit: Option<usize>,
+    /// Rover feed category, defaults to the first of `LINX_FEED_CATEGORY`
+    rover: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+struct SolSummary {
+    martian_sol_day: String,
+    terrestrial_date: NaiveDate,
+}
+
+/// Handler that lists all cached sols in ascending order
+#[utoipa::path(
+    get,
+    path = "/sols",
+    params(SolsQuery),
+    responses(
+        (status = 200, description = "Number of sols as `count` and a `soles` list of sol summaries"),
+        (status = 400, description = "Unknown rover", body = ErrorBody),
+    )
+)]
+async fn sols(
+    StrictQuery(params): StrictQuery<SolsQuery>,
+    State(state): State<Arc<SharedState>>,
+) -> Response {
+    let rover = match requested_rover(params.rover.as_deref(), &state.config) {
+        Ok(rover) => rover,
+        Err(err) => return err.into_response(),
+    };
+
+    let soles: Vec<SolSummary> = state
+        .cached_soles_data
+        .sols(rover)
+        .await
+        .into_iter()
+        .take(params.limit.unwrap_or(usize::MAX))
+        .map(|(sol, terrestrial_date)| SolSummary {
+            martian_sol_day: sol.0.to_string(),
+            terrestrial_date,
+        })
+        .collect();
+
+    Json(serde_json::json!({
+        "count": soles.len(),
+        "soles": soles
+    }))
+    .into_response()
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct WeatherQuery {
+    /// Date as %Y-%m-%d, %Y/%m/%d, %d-%m-%Y, rfc3339, %Y-%m-%dT%H:%M:%S in UTC or Unix timestamp in seconds
+    date: Option<String>,
+    /// First date of a range, used together with `to`
+    from: Option<String>,
+    /// Last date of a range, used together with `from`
+    to: Option<String>,
+    /// ISO week as %G-W%V, e.g. `2026-W07`, to get every sol from its Monday to its Sunday
+    week: Option<String>,
+    /// Temperature unit, one of `c` (default), `f` or `k`
+    units: Option<String>,
+    /// Response format, one of `json` (default), `csv` or `xml`. Takes precedence over the `Accept` header.
+    format: Option<String>,
+    /// Return the closest available sol when the requested sol has no data
+    nearest: Option<bool>,
+    /// Rover feed category, defaults to the first of `LINX_FEED_CATEGORY`
+    rover: Option<String>,
+    /// Comma-separated fields of JSON responses to return, e.g. `min_temp,max_temp`. The sol is always included.
+    fields: Option<String>,
+    /// Leave sols without both `min_temp` and `max_temp` out of range responses.
+    /// Their number is reported in the `X-Filtered-Sols` header.
+    require_temps: Option<bool>,
+}
+
+// Maximum number of Earth days that can be requested in a single range query
+const MAX_RANGE_DAYS: i64 = 100;
+
+/// Handler that serves weather data for requested date, or for each date in a range when both `from` and `to` are given
+#[utoipa::path(
+    get,
+    path = "/weather",
+    params(WeatherQuery),
+    responses(
+        (status = 200, description = "Weather data for the sol of `date`, or a list with an entry for every sol when `from` and `to` are given", body = WeatherResponse),
+        (status = 404, description = "No weather data for the sol of the date", body = ErrorBody),
+        (status = 304, description = "Data has not changed since the `If-None-Match` ETag or `If-Modified-Since` date"),
+        (status = 400, description = "Invalid date, range, units, format, rover or fields", body = ErrorBody),
+        (status = 429, description = "Rate limit exceeded. Retry after the number of seconds in `Retry-After`", body = ErrorBody),
+    )
+)]
+async fn weather(
+    StrictQuery(params): StrictQuery<WeatherQuery>,
+    IfNoneMatch(if_none_match): IfNoneMatch,
+    IfModifiedSince(if_modified_since): IfModifiedSince,
+    headers: HeaderMap,
+    uri: Uri,
+    State(state): State<Arc<SharedState>>,
+) -> Result<impl IntoResponse, ApiError> {
+    let updated_at = state.cached_soles_data.updated_at().await;
+    // The query string covers units, fields, compact, pretty and every other parameter changing the body
+    let format = negotiate(params.format.as_deref(), &headers).ok();
+    let etag = etag_for(
+        updated_at,
+        &format!("{format:?}?{}", uri.query().unwrap_or_default()),
+    );
+    let response = weather_response(params, &headers, &state).await?;
+
+    let response = with_etag(response, &etag, if_none_match.as_deref());
+    // If-Modified-Since is only considered when the client sent no ETag to compare against
+    let if_modified_since = if_modified_since.filter(|_| if_none_match.is_none());
+    Ok(with_last_modified(response, updated_at, if_modified_since))
+}
+
+/// Builds the response of /weather. Invalid parameters are returned as errors, while responses without data are
+/// built by the shared weather helpers.
+async fn weather_response(
+    params: WeatherQuery,
+    headers: &HeaderMap,
+    state: &SharedState,
+) -> Result<Response, ApiError> {
+    let units = parse_units(params.units.as_deref())?;
+    let format = negotiate(params.format.as_deref(), headers)
+        .map_err(|err| ApiError::InvalidFormat(err.to_string()))?;
+    let rover = requested_rover(params.rover.as_deref(), &state.config)?;
+    let fields = params
+        .fields
+        .as_deref()
+        .map(str::parse::<WeatherFields>)
+        .transpose()
+        .map_err(|err| ApiError::UnknownField(err.to_string()))?;
+
+    let require_temps = params.require_temps.unwrap_or(false);
+    if let (Some(from), Some(to)) = (params.from.as_deref(), params.to.as_deref()) {
+        let range = DateRange {
+            from: parse_valid_date(from)?,
+            to: parse_valid_date(to)?,
+            require_temps,
+        };
+        return Ok(weather_range(state, rover, range, units, format, fields.as_ref()).await);
+    }
+
+    if let Some(week) = params.week.as_deref() {
+        let (monday, sunday) =
+            parse_iso_week(week).map_err(|err| ApiError::InvalidWeek(err.to_string()))?;
+        let now = chrono::Utc::now();
+        let from = validate_date(monday.and_time(NaiveTime::default()).and_utc(), now)?;
+        // The current week is served up to today
+        let to = sunday
+            .min(now.date_naive())
+            .and_time(NaiveTime::default())
+            .and_utc();
+        let range = DateRange {
+            from,
+            to,
+            require_temps,
+        };
+        return Ok(weather_range(state, rover, range, units, format, fields.as_ref()).await);
+    }
+
+    if let Some(maybe_date) = params.date {
+        let date_in_martian_sols = sol_for_date(&maybe_date)?;
+
+        // Memoized responses are serialized with the default `N/A` for missing values
+        if format == ResponseFormat::Json
+            && fields.is_none()
+            && !params.nearest.unwrap_or(false)
+            && !explicit_nulls()
+        {
+            return Ok(memoized_weather_response(state, rover, date_in_martian_sols, units).await);
+        }
+
+        if params.nearest.unwrap_or(false) {
+            return Ok(nearest_weather_response(
+                date_in_martian_sols,
+                state
+                    .cached_soles_data
+                    .nearest_sol(rover, date_in_martian_sols)
+                    .await,
+                units,
+                format,
+                fields.as_ref(),
+            ));
+        }
+
+        Ok(weather_data_response(
+            state
+                .cached_soles_data
+                .get_data_for_sol(rover, date_in_martian_sols)
+                .await,
+            units,
+            format,
+            fields.as_ref(),
+        ))
+    } else {
+        Ok((StatusCode::OK, Json(serde_json::json!({
+            "message": "Send request with query parameter ?date=<requested date> or ?from=<first date>&to=<last date>. Allowed formats are %Y-%m-%d, %Y/%m/%d, %d-%m-%Y, rfc3339, %Y-%m-%dT%H:%M:%S in UTC and Unix timestamps in seconds."
+        }))).into_response())
+    }
+}
+
+/// Inclusive range of Earth dates requested with `from` and `to` or `week`
+struct DateRange {
+    from: chrono::DateTime<chrono::Utc>,
+    to: chrono::DateTime<chrono::Utc>,
+    require_temps: bool,
+}
+
+/// Rejects a response that would contain more sols than `LINX_MAX_SOLS_PER_RESPONSE` with `400 Bad Request`.
+/// Checked by every route returning several sols, on top of their own limits.
+fn too_many_sols(no_of_sols: usize, config: &Config) -> Result<(), ApiError> {
+    let max = config.max_sols_per_response;
+    if no_of_sols > max {
+        return Err(ApiError::TooManySols(format!(
+            "Response would contain {no_of_sols} sols. Maximum allowed is {max} sols per response."
+        )));
+    }
+    Ok(())
+}
+
+/// Serves weather data for every sol within the inclusive range of Earth dates
+async fn weather_range(
+    state: &SharedState,
+    rover: &str,
+    range: DateRange,
+    units: TemperatureUnit,
+    format: ResponseFormat,
+    fields: Option<&WeatherFields>,
+) -> Response {
+    let DateRange { from, to, .. } = range;
+
+    if from > to {
+        return ApiError::InvalidRange("Invalid range. from must not be after to.".to_string())
+            .into_response();
+    }
+
+    let no_of_days = (to - from).num_days() + 1;
+    if no_of_days > MAX_RANGE_DAYS {
+        return ApiError::RangeTooLarge(format!(
+            "Range spans {no_of_days} days. Maximum allowed is {MAX_RANGE_DAYS} days."
+        ))
+        .into_response();
+    }
+
+    // A sol is slightly longer than an Earth day, so consecutive days can map to the same sol
+    let mut sols = match (0..no_of_days)
+        .map(|day| calculate_no_of_martian_sol_elapsed(from + chrono::Days::new(day as u64)))
+        .collect::<anyhow::Result<Vec<i64>>>()
+    {
+        Ok(sols) => sols,
+        Err(err) => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "DATE_BEFORE_LANDING",
+                err.to_string(),
+            );
+        }
+    };
+    sols.dedup();
+    if let Err(err) = too_many_sols(sols.len(), &state.config) {
+        return err.into_response();
+    }
+
+    let snapshot = state.cached_soles_data.snapshot().await;
+    let mut soles: Vec<(i64, Option<SoleData>)> = sols
+        .into_iter()
+        .map(|sol| (sol, snapshot.get(rover, sol).cloned()))
+        .collect();
+
+    if range.require_temps {
+        let no_of_sols = soles.len();
+        soles.retain(|(_, data)| data.as_ref().is_some_and(SoleData::has_temps));
+        let response = weather_range_response(&soles, units, format, fields);
+        return with_filtered_sols(response, no_of_sols - soles.len());
+    }
+
+    weather_range_response(&soles, units, format, fields)
+}
+
+/// Renders range entries, marking sols without data
+fn weather_range_response(
+    soles: &[(i64, Option<SoleData>)],
+    units: TemperatureUnit,
+    format: ResponseFormat,
+    fields: Option<&WeatherFields>,
+) -> Response {
+    if format == ResponseFormat::Csv {
+        return csv_response(weather_csv(
+            soles.iter().map(|(sol, data)| (*sol, data.as_ref())),
+            units,
+        ));
+    }
+
+    let entries: Vec<WeatherRangeEntry> = soles
+        .iter()
+        .map(|(sol, data)| match data {
+            Some(data) => WeatherRangeEntry::Data(Box::new(WeatherResponse::new(data, units))),
+            None => WeatherRangeEntry::NoData {
+                martian_sol_day: sol.to_string(),
+                message: "No data found for sol",
+            },
+        })
+        .collect();
+
+    match format {
+        ResponseFormat::Xml => xml_response(weather_range_xml(&entries)),
+        _ => match fields {
+            Some(fields) => match entries
+                .iter()
+                .map(|entry| match entry {
+                    WeatherRangeEntry::Data(response) => {
+                        fields.project(response).map(serde_json::Value::Object)
+                    }
+                    WeatherRangeEntry::NoData { .. } => serde_json::to_value(entry),
+                })
+                .collect::<serde_json::Result<Vec<_>>>()
+            {
+                Ok(entries) => (StatusCode::OK, Json(entries)).into_response(),
+                Err(err) => json_serialization_failed(err),
+            },
+            None => (StatusCode::OK, Json(entries)).into_response(),
+        },
+    }
+}
+
+/// Adds the number of sols left out by `require_temps` to the response
+fn with_filtered_sols(mut response: Response, filtered: usize) -> Response {
+    response
+        .headers_mut()
+        .insert(X_FILTERED_SOLS, HeaderValue::from(filtered));
+    response
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct KelvinQuery {
+    /// Date as %Y-%m-%d, %Y/%m/%d, %d-%m-%Y, rfc3339, %Y-%m-%dT%H:%M:%S in UTC or Unix timestamp in seconds
+    date: Option<String>,
+    /// Rover feed category, defaults to the first of `LINX_FEED_CATEGORY`
+    rover: Option<String>,
+}
+
+/// Handler that always serves temperatures in Kelvin as numbers with one decimal place
+#[utoipa::path(
+    get,
+    path = "/weather/kelvin",
+    params(KelvinQuery),
+    responses(
+        (status = 200, description = "Weather data with temperatures in Kelvin", body = KelvinWeatherResponse),
+        (status = 404, description = "No weather data for the sol of the date", body = ErrorBody),
+        (status = 400, description = "Missing or invalid date or rover", body = ErrorBody),
+        (status = 429, description = "Rate limit exceeded", body = ErrorBody),
+    )
+)]
+async fn weather_kelvin(
+    StrictQuery(params): StrictQuery<KelvinQuery>,
+    State(state): State<Arc<SharedState>>,
+) -> Response {
+    let Some(maybe_date) = params.date else {
+        return ApiError::MissingDate(
+            "Send request with query parameter ?date=<requested date>.".to_string(),
+        )
+        .into_response();
+    };
+
+    let rover = match requested_rover(params.rover.as_deref(), &state.config) {
+        Ok(rover) => rover,
+        Err(err) => return err.into_response(),
+    };
+
+    let sol = match sol_for_date(&maybe_date) {
+        Ok(sol) => sol,
+        Err(err) => return ApiError::from(err).into_response(),
+    };
+
+    match state.cached_soles_data.get_data_for_sol(rover, sol).await {
+        Some(data) => (StatusCode::OK, Json(KelvinWeatherResponse::new(&data))).into_response(),
+        None => weather_data_response(None, TemperatureUnit::Kelvin, ResponseFormat::Json, None),
+    }
+}
+
+// Maximum number of dates in a single batch request
+const MAX_BATCH_DATES: usize = 100;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct WeatherBatchQuery {
+    /// Temperature unit, one of `c` (default), `f` or `k`
+    units: Option<String>,
+    /// Rover feed category, defaults to the first of `LINX_FEED_CATEGORY`
+    rover: Option<String>,
+}
+
+/// Handler that serves weather data for each date in a JSON array. Invalid dates get an error entry instead of
+/// failing the whole batch.
+#[utoipa::path(
+    post,
+    path = "/weather/batch",
+    params(WeatherBatchQuery),
+    request_body(content = Vec<String>, description = "Dates as %Y-%m-%d, %Y/%m/%d, %d-%m-%Y, rfc3339, %Y-%m-%dT%H:%M:%S in UTC or Unix timestamps in seconds", example = json!(["2026-02-09", "2026-01-15"])),
+    responses(
+        (status = 200, description = "An entry for every date, in the order of the request", body = Vec<WeatherBatchEntry>),
+        (status = 400, description = "Body is not a JSON array of strings, or invalid units or rover", body = ErrorBody),
+        (status = 413, description = "More than 100 dates", body = ErrorBody),
+        (status = 429, description = "Rate limit exceeded", body = ErrorBody),
+    )
+)]
+async fn weather_batch(
+    StrictQuery(params): StrictQuery<WeatherBatchQuery>,
+    State(state): State<Arc<SharedState>>,
+    body: Result<Json<Vec<String>>, JsonRejection>,
+) -> Response {
+    let dates = match body {
+        Ok(Json(dates)) => dates,
+        Err(err) => {
+            return ApiError::InvalidBody(format!(
+                "Send a JSON array of dates as body. {}",
+                err.body_text()
+            ))
+            .into_response();
+        }
+    };
+
+    if dates.len() > MAX_BATCH_DATES {
+        return ApiError::BatchTooLarge(format!(
+            "Batch contains {} dates. Maximum allowed is {MAX_BATCH_DATES} dates.",
+            dates.len()
+        ))
+        .into_response();
+    }
+    if let Err(err) = too_many_sols(dates.len(), &state.config) {
+        return err.into_response();
+    }
+
+    let units = match parse_units(params.units.as_deref()) {
+        Ok(units) => units,
+        Err(err) => return err.into_response(),
+    };
+
+    let rover = match requested_rover(params.rover.as_deref(), &state.config) {
+        Ok(rover) => rover,
+        Err(err) => return err.into_response(),
+    };
+
+    let snapshot = state.cached_soles_data.snapshot().await;
+    let mut entries = Vec::with_capacity(dates.len());
+    for date in dates {
+        let result = match sol_for_date(&date) {
+            Ok(sol) => WeatherBatchResult::Weather(match snapshot.get(rover, sol) {
+                Some(data) => WeatherRangeEntry::Data(Box::new(WeatherResponse::new(data, units))),
+                None => WeatherRangeEntry::NoData {
+                    martian_sol_day: sol.to_string(),
+                    message: "No data found for sol",
+                },
+            }),
+            Err(err) => WeatherBatchResult::Error(ErrorBody {
+                error: err.code().to_string(),
+                message: err.to_string(),
+                request_id: None,
+            }),
+        };
+        entries.push(WeatherBatchEntry { date, result });
+    }
+
+    (StatusCode::OK, Json(entries)).into_response()
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct DaylightQuery {
+    /// Date as %Y-%m-%d, %Y/%m/%d, %d-%m-%Y, rfc3339, %Y-%m-%dT%H:%M:%S in UTC or Unix timestamp in seconds
+    date: Option<String>,
+    /// Rover feed category, defaults to the first of `LINX_FEED_CATEGORY`
+    rover: Option<String>,
+}
+
+/// Handler that serves sunrise, sunset and the duration of daylight of the sol of requested date
+#[utoipa::path(
+    get,
+    path = "/weather/daylight",
+    params(DaylightQuery),
+    responses(
+        (status = 200, description = "Sunrise, sunset and duration of daylight", body = DaylightResponse),
+        (status = 404, description = "No weather data for the sol of the date, or no sunrise or sunset reported for it", body = ErrorBody),
+        (status = 400, description = "Missing or invalid date or rover", body = ErrorBody),
+        (status = 429, description = "Rate limit exceeded", body = ErrorBody),
+    )
+)]
+async fn weather_daylight(
+    StrictQuery(params): StrictQuery<DaylightQuery>,
+    State(state): State<Arc<SharedState>>,
+) -> Response {
+    let Some(maybe_date) = params.date else {
+        return ApiError::MissingDate(
+            "Send request with query parameter ?date=<requested date>.".to_string(),
+        )
+        .into_response();
+    };
+
+    let rover = match requested_rover(params.rover.as_deref(), &state.config) {
+        Ok(rover) => rover,
+        Err(err) => return err.into_response(),
+    };
+
+    let sol = match sol_for_date(&maybe_date) {
+        Ok(sol) => sol,
+        Err(err) => return ApiError::from(err).into_response(),
+    };
+
+    match state.cached_soles_data.get_data_for_sol(rover, sol).await {
+        Some(data) => match DaylightResponse::new(&data) {
+            Some(daylight) => (StatusCode::OK, Json(daylight)).into_response(),
+            None => ApiError::NotFound(
+                "NASA did not report both sunrise and sunset for the requested sol.".to_string(),
+            )
+            .into_response(),
+        },
+        None => weather_data_response(None, TemperatureUnit::default(), ResponseFormat::Json, None),
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct CompareQuery {
+    /// First date as %Y-%m-%d, %Y/%m/%d, %d-%m-%Y, rfc3339, %Y-%m-%dT%H:%M:%S in UTC or Unix timestamp in seconds
+    date1: Option<String>,
+    /// Second date, in the same formats as `date1`
+    date2: Option<String>,
+    /// Temperature unit, one of `c` (default), `f` or `k`
+    units: Option<String>,
+    /// Rover feed category, defaults to the first of `LINX_FEED_CATEGORY`
+    rover: Option<String>,
+}
+
+/// Handler that serves weather data of the sols of two dates side by side with the change in temperatures
+#[utoipa::path(
+    get,
+    path = "/weather/compare",
+    params(CompareQuery),
+    responses(
+        (status = 200, description = "Weather data of both dates, `null` for a date without data", body = WeatherCompareResponse),
+        (status = 400, description = "Missing or invalid date, unit or rover", body = ErrorBody),
+        (status = 429, description = "Rate limit exceeded", body = ErrorBody),
+    )
+)]
+async fn weather_compare(
+    StrictQuery(params): StrictQuery<CompareQuery>,
+    State(state): State<Arc<SharedState>>,
+) -> Result<Json<WeatherCompareResponse>, ApiError> {
+    let (Some(date1), Some(date2)) = (params.date1, params.date2) else {
+        return Err(ApiError::MissingDate(
+            "Send request with query parameters ?date1=<first date>&date2=<second date>."
+                .to_string(),
+        ));
+    };
+    let units = parse_units(params.units.as_deref())?;
+    let rover = requested_rover(params.rover.as_deref(), &state.config)?;
+    let sol1 = sol_for_date(&date1)?;
+    let sol2 = sol_for_date(&date2)?;
+
+    // Both sides are read from one snapshot so an update in between can not mix old and new data
+    let snapshot = state.cached_soles_data.snapshot().await;
+    Ok(Json(WeatherCompareResponse::new(
+        (sol1, snapshot.get(rover, sol1)),
+        (sol2, snapshot.get(rover, sol2)),
+        units,
+    )))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct WeatherBySolQuery {
+    /// Temperature unit, one of `c` (default), `f` or `k`
+    units: Option<String>,
+    /// Response format, one of `json` (default), `csv` or `xml`. Takes precedence over the `Accept` header.
+    format: Option<String>,
+    /// Rover feed category, defaults to the first of `LINX_FEED_CATEGORY`
+    rover: Option<String>,
+}
+
+/// Handler that serves weather data for requested Martian sol
+#[utoipa::path(
+    get,
+    path = "/weather/sol/{sol}",
+    params(("sol" = i64, Path, description = "Martian sol"), WeatherBySolQuery),
+    responses(
+        (status = 200, description = "Weather data for the sol", body = WeatherResponse),
+        (status = 404, description = "No weather data for the sol. Sols outside the cached range also get the range.", body = SolOutOfRangeBody),
+        (status = 400, description = "Invalid sol, units, format or rover", body = ErrorBody),
+        (status = 429, description = "Rate limit exceeded", body = ErrorBody),
+    )
+)]
+async fn weather_by_sol(
+    Path(maybe_sol): Path<String>,
+    StrictQuery(params): StrictQuery<WeatherBySolQuery>,
+    headers: HeaderMap,
+    State(state): State<Arc<SharedState>>,
+) -> impl IntoResponse {
+    let sol = match maybe_sol.parse::<i64>() {
+        Ok(sol) => Sole(sol),
+        Err(_) => {
+            return ApiError::InvalidSol("Invalid sol. Sol must be a whole number.".to_string())
+                .into_response();
+        }
+    };
+
+    let units = match parse_units(params.units.as_deref()) {
+        Ok(units) => units,
+        Err(err) => return err.into_response(),
+    };
+
+    let format = match negotiate(params.format.as_deref(), &headers) {
+        Ok(format) => format,
+        Err(err) => return ApiError::InvalidFormat(err.to_string()).into_response(),
+    };
+
+    let rover = match requested_rover(params.rover.as_deref(), &state.config) {
+        Ok(rover) => rover,
+        Err(err) => return err.into_response(),
+    };
+
+    let response = if format == ResponseFormat::Json && !explicit_nulls() {
+        memoized_weather_response(&state, rover, sol.clone(), units).await
+    } else {
+        weather_data_response(
+            state
+                .cached_soles_data
+                .get_data_for_sol(rover, sol.clone())
+                .await,
+            units,
+            format,
+            None,
+        )
+    };
+
+    if response.status() == StatusCode::NOT_FOUND
+        && let Some((first_sol, last_sol)) =
+            state.cached_soles_data.snapshot().await.sol_bounds(rover)
+        && !(first_sol..=last_sol).contains(&sol.0)
+    {
+        return error_response_with(
+            StatusCode::NOT_FOUND,
+            "NO_DATA",
+            format!(
+                "No weather data found for sol {}. Cached sols of rover {rover} range from {first_sol} to {last_sol}.",
+                sol.0
+            ),
+            |error| SolOutOfRangeBody {
+                error,
+                first_sol,
+                last_sol,
+            },
+        );
+    }
+    response
+}
+
+/// Handler that serves weather data for the most recent cached sol
+#[utoipa::path(
+    get,
+    path = "/weather/latest",
+    params(WeatherBySolQuery),
+    responses(
+        (status = 200, description = "Weather data for the most recent sol", body = WeatherResponse),
+        (status = 404, description = "No weather data cached", body = ErrorBody),
+        (status = 400, description = "Invalid units, format or rover", body = ErrorBody),
+        (status = 429, description = "Rate limit exceeded", body = ErrorBody),
+    )
+)]
+async fn weather_latest(
+    StrictQuery(params): StrictQuery<WeatherBySolQuery>,
+    headers: HeaderMap,
+    State(state): State<Arc<SharedState>>,
+) -> Response {
+    let units = match parse_units(params.units.as_deref()) {
+        Ok(units) => units,
+        Err(err) => return err.into_response(),
+    };
+
+    let format = match negotiate(params.format.as_deref(), &headers) {
+        Ok(format) => format,
+        Err(err) => return ApiError::InvalidFormat(err.to_string()).into_response(),
+    };
+
+    let rover = match requested_rover(params.rover.as_deref(), &state.config) {
+        Ok(rover) => rover,
+        Err(err) => return err.into_response(),
+    };
+
+    weather_data_response(
+        state.cached_soles_data.latest(rover).await,
+        units,
+        format,
+        None,
+    )
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct WeatherStatsQuery {
+    /// Temperature unit, one of `c` (default), `f` or `k`
+    units: Option<String>,
+    /// Rover feed category, defaults to the first of `LINX_FEED_CATEGORY`
+    rover: Option<String>,
+}
+
+/// Handler that serves aggregates across every cached sol
+#[utoipa::path(
+    get,
+    path = "/weather/stats",
+    params(WeatherStatsQuery),
+    responses(
+        (status = 200, description = "Aggregates across every cached sol", body = WeatherStatsResponse),
+        (status = 404, description = "No weather data cached", body = ErrorBody),
+        (status = 400, description = "Invalid units or rover", body = ErrorBody),
+        (status = 429, description = "Rate limit exceeded", body = ErrorBody),
+    )
+)]
+async fn weather_stats(
+    StrictQuery(params): StrictQuery<WeatherStatsQuery>,
+    State(state): State<Arc<SharedState>>,
+) -> Response {
+    let units = match parse_units(params.units.as_deref()) {
+        Ok(units) => units,
+        Err(err) => return err.into_response(),
+    };
+
+    let rover = match requested_rover(params.rover.as_deref(), &state.config) {
+        Ok(rover) => rover,
+        Err(err) => return err.into_response(),
+    };
+
+    match state.cached_soles_data.snapshot().await.stats(rover, units) {
+        Some(stats) => Json(stats).into_response(),
+        None => {
+            ApiError::NotFound(format!("No weather data cached for rover {rover}.")).into_response()
+        }
+    }
+}
+
+// Default and maximum number of coldest and warmest sols in extremes queries
+const DEFAULT_EXTREMES: usize = 5;
+const MAX_EXTREMES: usize = 50;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct WeatherExtremesQuery {
+    /// Number of coldest and of warmest sols, defaults to 5 and may be at most 50
+    n: Option<usize>,
+    /// Temperature unit, one of `c` (default), `f` or `k`
+    units: Option<String>,
+    /// Rover feed category, defaults to the first of `LINX_FEED_CATEGORY`
+    rover: Option<String>,
+}
+
+/// Handler that serves the coldest and warmest cached sols
+#[utoipa::path(
+    get,
+    path = "/weather/extremes",
+    params(WeatherExtremesQuery),
+    responses(
+        (status = 200, description = "Coldest and warmest cached sols", body = WeatherExtremesResponse),
+        (status = 404, description = "No weather data cached", body = ErrorBody),
+        (status = 400, description = "Invalid n, units or rover", body = ErrorBody),
+        (status = 429, description = "Rate limit exceeded", body = ErrorBody),
+    )
+)]
+async fn weather_extremes(
+    StrictQuery(params): StrictQuery<WeatherExtremesQuery>,
+    State(state): State<Arc<SharedState>>,
+) -> Result<Json<WeatherExtremesResponse>, ApiError> {
+    let n = params.n.unwrap_or(DEFAULT_EXTREMES);
+    if !(1..=MAX_EXTREMES).contains(&n) {
+        return Err(ApiError::InvalidCount(format!(
+            "Invalid n {n}. n must be between 1 and {MAX_EXTREMES} sols."
+        )));
+    }
+    // Both lists may hold n sols
+    too_many_sols(2 * n, &state.config)?;
+
+    let units = parse_units(params.units.as_deref())?;
+    let rover = requested_rover(params.rover.as_deref(), &state.config)?;
+
+    let snapshot = state.cached_soles_data.snapshot().await;
+    if snapshot.sol_bounds(rover).is_none() {
+        return Err(ApiError::NotFound(format!(
+            "No weather data cached for rover {rover}."
+        )));
+    }
+    Ok(Json(snapshot.extremes(rover, n, units)))
+}
+
+// Default and maximum number of sols on each side of the requested sol in window queries
+const DEFAULT_WINDOW_RADIUS: i64 = 3;
+const MAX_WINDOW_RADIUS: i64 = 50;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+struct WeatherWindowQuery {
+    /// Date as %Y-%m-%d, %Y/%m/%d, %d-%m-%Y, rfc3339, %Y-%m-%dT%H:%M:%S in UTC or Unix timestamp in seconds
+    date: Option<String>,
+    /// Number of sols on each side of the sol of `date`, defaults to 3 and may be at most 50
+    radius: Option<i64>,
+    /// Temperature unit, one of `c` (default), `f` or `k`
+    units: Option<String>,
+    /// Rover feed category, defaults to the first of `LINX_FEED_CATEGORY`
+    rover: Option<String>,
+    /// Leave sols without both `min_temp` and `max_temp` out of the aggregates.
+    /// Their number is reported in the `X-Filtered-Sols` header.
+    require_temps: Option<bool>,
+}
+
+/// Handler that serves the lowest and highest temperatures across the sols around the sol of requested date
+#[utoipa::path(
+    get,
+    path = "/weather/window",
+    params(WeatherWindowQuery),
+    responses(
+        (status = 200, description = "Temperature extremes of the sols in the window", body = WeatherWindowResponse),
+        (status = 400, description = "Missing or invalid date, radius, units or rover", body = ErrorBody),
+        (status = 429, description = "Rate limit exceeded", body = ErrorBody),
+    )
+)]
+async fn weather_window(
+    StrictQuery(params): StrictQuery<WeatherWindowQuery>,
+    State(state): State<Arc<SharedState>>,
+) -> Response {
+    let Some(maybe_date) = params.date else {
+        return ApiError::MissingDate(
+            "Send request with query parameter ?date=<requested date>.".to_string(),
+        )
+        .into_response();
+    };
+
+    let radius = params.radius.unwrap_or(DEFAULT_WINDOW_RADIUS);
+    if !(0..=MAX_WINDOW_RADIUS).contains(&radius) {
+        return ApiError::InvalidRadius(format!(
+            "Invalid radius {radius}. Radius must be between 0 and {MAX_WINDOW_RADIUS} sols."
+        ))
+        .into_response();
+    }
+    if let Err(err) = too_many_sols(2 * radius as usize + 1, &state.config) {
+        return err.into_response();
+    }
+
+    let units = match parse_units(params.units.as_deref()) {
+        Ok(units) => units,
+        Err(err) => return err.into_response(),
+    };
+
+    let rover = match requested_rover(params.rover.as_deref(), &state.config) {
+        Ok(rover) => rover,
+        Err(err) => return err.into_response(),
+    };
+
+    let sol = match sol_for_date(&maybe_date) {
+        Ok(sol) => sol,
+        Err(err) => return ApiError::from(err).into_response(),
+    };
+
+    let mut soles = state.cached_soles_data.window(rover, sol, radius).await;
+    if params.require_temps.unwrap_or(false) {
+        // Sols missing from the cache count as filtered too
+        let no_of_sols = (2 * radius + 1) as usize;
+        soles.retain(SoleData::has_temps);
+        let response = Json(WeatherWindowResponse::new(sol, radius, &soles, units)).into_response();
+        return with_filtered_sols(response, no_of_sols - soles.len());
+    }
+
+    (
+        StatusCode::OK,
+        Json(WeatherWindowResponse::new(sol, radius, &soles, units)),
+    )
+        .into_response()
+}
+
+fn error_response(status: StatusCode, error: &str, message: String) -> Response {
+    error_response_with(status, error, message, |body| body)
+}
+
+/// Builds an error response whose body wraps the [`ErrorBody`] with `body`, e.g. to add fields to it
+fn error_response_with<T: Serialize>(
+    status: StatusCode,
+    error: &str,
+    message: String,
+    body: impl FnOnce(ErrorBody) -> T,
+) -> Response {
+    // Messages without a translation in the requested language are served in English
+    let (message, language) = match language::translate(&message, language::current()) {
+        Some(translated) => (translated.to_string(), language::current()),
+        None => (message, Language::English),
+    };
+    let response = (
+        status,
+        Json(body(ErrorBody {
+            error: error.to_string(),
+            message,
+            request_id: request_id::current(),
+        })),
+    )
+        .into_response();
+    with_content_language(response, language)
+}
+
+/// Builds the weather response shared by all weather routes. Responds with `404 Not Found` when there is no data.
+fn weather_data_response(
+    data: Option<SoleData>,
+    units: TemperatureUnit,
+    format: ResponseFormat,
+    fields: Option<&WeatherFields>,
+) -> Response {
+    match data {
+        Some(data) => render_weather(
+            &data,
+            WeatherResponse::new(&data, units),
+            units,
+            format,
+            fields,
+        ),
+        None => ApiError::NotFound("No weather data found for the requested sol.".to_string())
+            .into_response(),
+    }
+}
+
+/// Builds the JSON weather response from the memoized serialization of the sol
+async fn memoized_weather_response(
+    state: &SharedState,
+    rover: &str,
+    sol: impl Into<Sole>,
+    units: TemperatureUnit,
+) -> Response {
+    match state
+        .cached_soles_data
+        .weather_json(rover, sol, units)
+        .await
+    {
+        Ok(Some(json)) => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "application/json")],
+            json,
+        )
+            .into_response(),
+        Ok(None) => weather_data_response(None, units, ResponseFormat::Json, None),
+        Err(err) => json_serialization_failed(err),
+    }
+}
+
+/// Builds the weather response for the sol closest to the requested sol, noting the difference between them
+fn nearest_weather_response(
+    requested_sol: i64,
+    data: Option<SoleData>,
+    units: TemperatureUnit,
+    format: ResponseFormat,
+    fields: Option<&WeatherFields>,
+) -> Response {
+    match data {
+        Some(data) => render_weather(
+            &data,
+            WeatherResponse::new(&data, units).with_requested_sol(requested_sol),
+            units,
+            format,
+            fields,
+        ),
+        None => weather_data_response(None, units, format, fields),
+    }
+}
+
+fn render_weather(
+    data: &SoleData,
+    response: WeatherResponse,
+    units: TemperatureUnit,
+    format: ResponseFormat,
+    fields: Option<&WeatherFields>,
+) -> Response {
+    match format {
+        ResponseFormat::Json => match fields.map(|fields| fields.project(&response)) {
+            None => (StatusCode::OK, Json(response)).into_response(),
+            Some(Ok(projected)) => (StatusCode::OK, Json(projected)).into_response(),
+            Some(Err(err)) => json_serialization_failed(err),
+        },
+        ResponseFormat::Csv => csv_response(weather_csv([(data.sol.0, Some(data))], units)),
+        ResponseFormat::Xml => xml_response(weather_xml(&response)),
+    }
+}
+
+fn json_serialization_failed(err: serde_json::Error) -> Response {
+    tracing::error!("Failed to serialize JSON response: {err}");
+    error_response(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "SERIALIZATION_FAILED",
+        "Unable to serialize response as JSON.".to_string(),
+    )
+}
+
+fn xml_response(body: anyhow::Result<String>) -> Response {
+    match body {
+        Ok(body) => (
+            StatusCode::OK,
+            [(header::CONTENT_TYPE, "application/xml; charset=utf-8")],
+            body,
+        )
+            .into_response(),
+        Err(err) => {
+            tracing::error!("Failed to serialize XML response: {err}");
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "SERIALIZATION_FAILED",
+                "Unable to serialize response as XML.".to_string(),
+            )
+        }
+    }
+}
+
+fn csv_response(body: String) -> Response {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/csv; charset=utf-8")],
+        body,
+    )
+        .into_response()
+}
+
+// Longest accepted date, well above rfc3339 with nanoseconds and an offset
+const MAX_DATE_LEN: usize = 64;
+
+/// Parses a requested date and checks that weather data can exist for it
+fn parse_valid_date(maybe_date: &str) -> Result<chrono::DateTime<chrono::Utc>, DateError> {
+    let datetime = parse_date_from_string(maybe_date)
+        .map_err(|err| DateError::InvalidFormat(err.to_string()))?;
+    validate_date(datetime, chrono::Utc::now())
+}
+
+/// Parses the requested temperature unit, defaulting to Celsius
+fn parse_units(units: Option<&str>) -> Result<TemperatureUnit, ApiError> {
+    units
+        .map(str::parse::<TemperatureUnit>)
+        .transpose()
+        .map(Option::unwrap_or_default)
+        .map_err(|err| ApiError::InvalidUnit(err.to_string()))
+}
+
+/// Resolves the requested rover, defaulting to the first configured feed category. Only rovers with a configured
+/// feed category are allowed.
+fn requested_rover<'a>(rover: Option<&'a str>, config: &'a Config) -> Result<&'a str, ApiError> {
+    let Some(rover) = rover.or(config.feed_categories.first().map(String::as_str)) else {
+        return Err(ApiError::UnknownRover(
+            "No rover is configured. Set LINX_FEED_CATEGORY.".to_string(),
+        ));
+    };
+    if config
+        .feed_categories
+        .iter()
+        .any(|category| category == rover)
+    {
+        Ok(rover)
+    } else {
+        Err(ApiError::UnknownRover(format!(
+            "Unknown rover '{rover}'. Available rovers are {}.",
+            config.feed_categories.join(", ")
+        )))
+    }
+}
+
+/// Parses and validates a requested date and converts it to the Martian sol it falls on
+fn sol_for_date(maybe_date: &str) -> Result<i64, DateError> {
+    let datetime = parse_valid_date(maybe_date)?;
+    calculate_no_of_martian_sol_elapsed(datetime).map_err(|_| DateError::BeforeLanding)
+}
+
+/// Parses an ISO week such as `2026-W07` into the dates of its Monday and Sunday
+fn parse_iso_week(week: &str) -> anyhow::Result<(NaiveDate, NaiveDate)> {
+    let invalid_week = || anyhow!("Invalid week '{week}'. Expected an ISO week such as 2026-W07.");
+
+    let (year, week_no) = week.split_once("-W").ok_or_else(invalid_week)?;
+    let (Ok(year), Ok(week_no)) = (year.parse::<i32>(), week_no.parse::<u32>()) else {
+        return Err(invalid_week());
+    };
+    let monday =
+        NaiveDate::from_isoywd_opt(year, week_no, chrono::Weekday::Mon).ok_or_else(invalid_week)?;
+    let sunday =
+        NaiveDate::from_isoywd_opt(year, week_no, chrono::Weekday::Sun).ok_or_else(invalid_week)?;
+    Ok((monday, sunday))
+}
+
+// Formats of dates without time, in order of precedence
+const DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%Y/%m/%d", "%d-%m-%Y"];
+
+// Format of rfc3339 date times without offset, which are taken as UTC
+const NAIVE_DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+fn parse_date_from_string(maybe_date: &str) -> anyhow::Result<chrono::DateTime<chrono::Utc>> {
+    // Rejected before parsing or logging so pathological input never reaches the logs or the chrono parsers
+    if maybe_date.len() > MAX_DATE_LEN {
+        return Err(anyhow!(
+            "Invalid date. Date must be at most {MAX_DATE_LEN} characters long."
+        ));
+    }
+    tracing::info!("Parsing date: {maybe_date}");
+    // Tried in order, so the ISO format wins over the alternatives for input like 10-02-03
+    let naive_date = DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(maybe_date, format).ok());
+    let rfc3339_date = DateTime::parse_from_rfc3339(maybe_date);
+    let naive_datetime = NaiveDateTime::parse_from_str(maybe_date, NAIVE_DATETIME_FORMAT).ok();
+    // Unix timestamp in seconds. Timestamps out of range for a date are rejected.
+    let epoch_date = Some(maybe_date)
+        .filter(|maybe_date| {
+            !maybe_date.is_empty() && maybe_date.bytes().all(|b| b.is_ascii_digit())
+        })
+        .and_then(|maybe_date| maybe_date.parse::<i64>().ok())
+        .and_then(|secs| DateTime::from_timestamp(secs, 0));
+
+    match (naive_date, rfc3339_date, naive_datetime, epoch_date) {
+        (_, Ok(rfc3339_date), _, _) => Ok(rfc3339_date.to_utc()),
+        (_, _, Some(naive_datetime), _) => Ok(naive_datetime.and_utc()),
+        (Some(naive_date), _, _, _) => {
+            Ok(NaiveDateTime::new(naive_date, NaiveTime::default()).and_utc())
+        }
+        (_, _, _, Some(epoch_date)) => Ok(epoch_date),
+        (None, rfc3339_date_err, None, None) => {
+            tracing::error!(
+                "Date matches none of {DATE_FORMATS:?}, rfc3339_date_err: {:#?}",
+                rfc3339_date_err
+            );
+            Err(anyhow!(
+                "Invalid format for date. Allowed formats are %Y-%m-%d, %Y/%m/%d, %d-%m-%Y, rfc3339, %Y-%m-%dT%H:%M:%S in UTC and Unix timestamps in seconds."
+            ))
+        }
+    }
+}
+
+/// Payload of the NASA weather feed. Rows are parsed one by one so a malformed row does not fail the whole payload.
+#[derive(Debug, Deserialize)]
+struct NasaData {
+    soles: Vec<serde_json::Value>,
+}
+
+/// Parses soles data rows, skipping and logging rows that fail to parse
+fn parse_sole_rows(rows: &[serde_json::Value]) -> Vec<SoleData> {
+    let soles: Vec<SoleData> = rows
+        .iter()
+        .enumerate()
+        .filter_map(
+            |(index, row)| match serde_path_to_error::deserialize::<_, SoleData>(row) {
+                Ok(data) => Some(data),
+                Err(err) => {
+                    tracing::warn!(
+                        "Skipping soles[{index}]. Failed to parse {}: {}. Row: {}",
+                        err.path(),
+                        err.inner(),
+                        truncate_for_log(&row.to_string())
+                    );
+                    None
+                }
+            },
+        )
+        .collect();
+
+    let skipped = rows.len() - soles.len();
+    if skipped > 0 {
+        tracing::warn!("Skipped {skipped} of {} soles data rows", rows.len());
+    }
+    soles
+}
+
+/// Soles data of a rover fetched from NASA along with the raw payload it was parsed from
+struct FetchedSoles {
+    soles: SolesData,
+    raw: serde_json::Value,
+    /// Number of implausible rows, see [`anomalies::validate_soles`]
+    anomalies: usize,
+    /// Sent with the next fetch of the rover so NASA only responds with the feed when it has changed
+    validators: FeedValidators,
+}
+
+/// Outcome of fetching a rover's feed from NASA
+enum FetchOutcome {
+    Modified(FetchedSoles),
+    /// NASA responded with `304 Not Modified`, so cached data is still current
+    NotModified,
+}
+
+impl FetchOutcome {
+    /// Returns the fetched soles data, unless the feed has not changed
+    fn modified(self) -> Option<FetchedSoles> {
+        match self {
+            FetchOutcome::Modified(fetched) => Some(fetched),
+            FetchOutcome::NotModified => None,
+        }
+    }
+}
+
+impl FetchedSoles {
+    /// Records the payload for /raw/history, fetched now
+    fn history_entry(&self, rover: &str) -> RawHistoryEntry {
+        RawHistoryEntry {
+            rover: rover.to_string(),
+            fetched_at: chrono::Utc::now(),
+            sol_count: self.soles.len(),
+            payload: self.raw.clone(),
+        }
+    }
+}
+
+/// Saves soles data to the database, logging instead of failing since the database is only a fallback
+async fn persist_soles_data(db_path: PathBuf, data: SolesData) {
+    let result = tokio::task::spawn_blocking(move || storage::save_soles(&db_path, &data)).await;
+    match result {
+        Ok(Ok(())) => tracing::info!("Saved soles data to database"),
+        Ok(Err(err)) => tracing::error!("Unable to save soles data to database. Err: {err}"),
+        Err(err) => tracing::error!("Unable to save soles data to database. Err: {err}"),
+    }
+}
+
+// Retries of a failed fetch are delayed by 1s, 2s and 4s
+const FETCH_RETRIES: u32 = 3;
+const FETCH_BASE_DELAY: Duration = Duration::from_secs(1);
+
+// Delay between startup attempts when LINX_WAIT_FOR_DATA is set and no soles data is available
+const WAIT_FOR_DATA_DELAY: Duration = Duration::from_secs(60);
+
+// Maximum number of characters of upstream bodies included in logs
+const LOG_SNIPPET_CHARS: usize = 500;
+
+/// Shortens text to a snippet suitable for logs
+fn truncate_for_log(text: &str) -> String {
+    match text.char_indices().nth(LOG_SNIPPET_CHARS) {
+        Some((end, _)) => format!("{}... ({} bytes total)", &text[..end], text.len()),
+        None => text.to_string(),
+    }
+}
+
+/// Fetches soles data of every rover category concurrently, returning the result for each rover.
+/// Rovers with `validators` are fetched conditionally.
+async fn fetch_rovers_soles_data(
+    client: &reqwest::Client,
+    config: &Config,
+    validators: &HashMap<String, FeedValidators>,
+    retries: u32,
+    base_delay: Duration,
+) -> Vec<(String, anyhow::Result<FetchOutcome>)> {
+    let unknown = FeedValidators::default();
+    futures::future::join_all(config.feed_categories.iter().map(|category| {
+        let validators = validators.get(category).unwrap_or(&unknown);
+        async move {
+            let result = fetch_soles_data(
+                client,
+                &config.feed_url,
+                category,
+                validators,
+                retries,
+                base_delay,
+            )
+            .await
+            .map(|outcome| match outcome {
+                FetchOutcome::Modified(fetched) => {
+                    let (soles, anomalies) = validate_soles(fetched.soles, config.drop_anomalies);
+                    FetchOutcome::Modified(FetchedSoles {
+                        soles,
+                        anomalies,
+                        ..fetched
+                    })
+                }
+                FetchOutcome::NotModified => FetchOutcome::NotModified,
+            });
+            (category.clone(), result)
+        }
+    }))
+    .await
+}
+
+/// Fetches soles data, retrying up to `retries` times with exponential backoff starting at `base_delay`.
+/// Timeouts are retried like any other failure. When NASA is busy and sends `Retry-After`, that delay is
+/// used instead of the backoff.
+async fn fetch_soles_data(
+    client: &reqwest::Client,
+    feed_url: &str,
+    category: &str,
+    validators: &FeedValidators,
+    retries: u32,
+    base_delay: Duration,
+) -> anyhow::Result<FetchOutcome> {
+    let mut attempt = 0;
+    loop {
+        match fetch_soles_data_once(client, feed_url, category, validators).await {
+            Ok(data) => return Ok(data),
+            Err(err) if attempt < retries => {
+                let delay = retry_after(&err).unwrap_or(base_delay * 2u32.pow(attempt));
+                attempt += 1;
+                tracing::warn!(
+                    "Fetch attempt {attempt} of {} failed. Retrying in {delay:?}. Err: {err}",
+                    retries + 1
+                );
+                tokio::time::sleep(delay).await;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+async fn fetch_soles_data_once(
+    client: &reqwest::Client,
+    feed_url: &str,
+    category: &str,
+    validators: &FeedValidators,
+) -> anyhow::Result<FetchOutcome> {
+    // Skips parsing when the feed has not changed since the validators were taken
+    let (body, validators) = match fetch_feed_body(client, feed_url, category, validators).await? {
+        FeedBody::Modified { body, validators } => (body, validators),
+        FeedBody::NotModified => return Ok(FetchOutcome::NotModified),
+    };
+
+    let raw = match serde_json::from_str::<serde_json::Value>(&body) {
+        Ok(raw) => raw,
+        Err(err) => {
+            tracing::error!(
+                "Soles data is not valid JSON: {err}. Body: {}",
+                truncate_for_log(&body)
+            );
+            return Err(anyhow!("Soles data is not valid JSON: {err}"));
+        }
+    };
+
+    // Feed shape is chosen by category, since InSight keys sols by number instead of listing them
+    let parsed = if is_insight_category(category) {
+        serde_path_to_error::deserialize::<_, NasaInSightData>(&raw)
+            .map(|data| parse_insight_sols(&data))
+    } else {
+        serde_path_to_error::deserialize::<_, NasaData>(&raw)
+            .map(|data| (data.soles.len(), parse_sole_rows(&data.soles)))
+    };
+    let (no_of_rows, soles) = match parsed {
+        Ok(parsed) => parsed,
+        Err(err) => {
+            tracing::error!(
+                "Failed to parse soles data at {}: {}. Body: {}",
+                err.path(),
+                err.inner(),
+                truncate_for_log(&body)
+            );
+            return Err(anyhow!(
+                "Failed to parse soles data at {}: {}",
+                err.path(),
+                err.inner()
+            ));
+        }
+    };
+
+    // A valid but empty feed is treated as a failure so it is retried and never replaces cached data
+    if no_of_rows == 0 {
+        tracing::warn!("Feed {category} returned no soles");
+        return Err(anyhow!("Feed {category} returned no soles"));
+    }
+
+    // Keeps previously cached data instead of replacing it with nothing when every row is malformed
+    if soles.is_empty() {
+        return Err(anyhow!("Failed to parse all {no_of_rows} soles data rows"));
+    }
+
+    Ok(FetchOutcome::Modified(FetchedSoles {
+        soles: dedup_soles(category, soles),
+        raw,
+        anomalies: 0,
+        validators,
+    }))
+}
+
+/// Keys soles data by rover and sol. When the feed contains a sol more than once, the row with the most
+/// temperatures is kept, then the one with the latest terrestrial date, then the first one.
+fn dedup_soles(category: &str, soles: Vec<SoleData>) -> SolesData {
+    let preference = |data: &SoleData| {
+        (
+            data.min_temp.is_some() as u8 + data.max_temp.is_some() as u8,
+            data.terrestrial_date,
+        )
+    };
+
+    let mut deduped = SolesData::with_capacity(soles.len());
+    for sole in soles {
+        match deduped.entry((category.to_string(), sole.sol.clone())) {
+            Entry::Vacant(entry) => {
+                entry.insert(sole);
+            }
+            Entry::Occupied(mut entry) => {
+                tracing::warn!("Feed {category} contains sol {} more than once", sole.sol.0);
+                if preference(&sole) > preference(entry.get()) {
+                    entry.insert(sole);
+                }
+            }
+        }
+    }
+    deduped
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_compression() {
+        let response = test_router([])
+            .oneshot(
+                Request::builder()
+                    .uri("/")
+                    .header(header::ACCEPT_ENCODING, "gzip")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+    }
+
+    /// Router over a cache seeded with the given msl sols, without network access or background tasks
+    fn test_router(sols: impl IntoIterator<Item = SoleData>) -> Router {
+        test_router_with_config(Config::fixture(), sols)
+    }
+
+    fn test_router_with_config(config: Config, sols: impl IntoIterator<Item = SoleData>) -> Router {
+        build_app(test_state(config, sols))
+    }
+
+    /// State behind [`test_router_with_config`], for tests that record metrics or history before building the app
+    fn test_state(config: Config, sols: impl IntoIterator<Item = SoleData>) -> Arc<SharedState> {
+        let soles_data = sols
+            .into_iter()
+            .map(|data| (("msl".to_string(), data.sol.clone()), data))
+            .collect();
+        Arc::new(SharedState {
+            rate_limiter: RateLimiter::new(config.rate_limit_per_minute),
+            raw_history: RawHistory::new(config.raw_history_size),
+            config,
+            http_client: reqwest::Client::new(),
+            cached_soles_data: CachedSolesData::new(soles_data, HashMap::new(), HashMap::new()),
+            metrics: Metrics::default(),
+        })
+    }
+
+    async fn get_json(app: Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = app
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_weather_by_date() {
+        let app = test_router([SoleData::fixture(4803), SoleData::fixture(4804)]);
+
+        let (status, body) = get_json(app, "/weather?date=2026-02-10&units=f").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "martian_sol_day": "4804",
+                "terrestrial_date": "2026-02-10",
+                "sol_start": "2026-02-09T06:15:21Z",
+                "sol_end": "2026-02-10T06:54:56Z",
+                "sol_drift_seconds": 2375.245,
+                "min_temp": "-101.2",
+                "max_temp": "19.4",
+                "units": "f",
+                "temperature": {"min": -101.2, "max": 19.4, "mean": -40.9, "unit": "f"},
+                "pressure": "750",
+                "atmo_opacity": "Sunny",
+                "season": "Month 4",
+                "sunrise": "05:19:00",
+                "sunset": "17:22:00",
+                "tz_label": "Mars local mean solar time"
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_weather_without_data() {
+        let app = test_router([SoleData::fixture(4803)]);
+
+        let (status, body) = get_json(app, "/weather?date=2026-02-10").await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "NO_DATA");
+    }
+
+    #[tokio::test]
+    async fn test_weather_rejects_invalid_query() {
+        let app = test_router([SoleData::fixture(4804)]);
+
+        let (status, body) = get_json(app.clone(), "/weather?date=2026-13-01").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "INVALID_DATE_FORMAT");
+
+        let (status, body) = get_json(app, "/weather?date=2026-02-10&units=r").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "INVALID_UNIT");
+    }
+
+    #[tokio::test]
+    async fn test_weather_by_sol_out_of_range() {
+        let app = test_router([
+            SoleData::fixture(4790),
+            SoleData::fixture(4800),
+            SoleData::fixture(4805),
+        ]);
+
+        let (status, body) = get_json(app.clone(), "/weather/sol/9000").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "NO_DATA");
+        assert_eq!(
+            body["message"],
+            "No weather data found for sol 9000. Cached sols of rover msl range from 4790 to 4805."
+        );
+        assert_eq!(
+            (body["first_sol"].as_i64(), body["last_sol"].as_i64()),
+            (Some(4790), Some(4805))
+        );
+
+        // Gaps within the range get the usual response
+        let (status, body) = get_json(app, "/weather/sol/4795").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "NO_DATA");
+        assert!(body.get("first_sol").is_none());
+    }
+
+    /// Fails with every violation when the value does not match `schemas/weather_response.schema.json`
+    fn assert_matches_weather_schema(uri: &str, value: &serde_json::Value) {
+        let schema = serde_json::from_str(include_str!("../schemas/weather_response.schema.json"))
+            .expect("Weather response schema is not valid JSON");
+        let validator =
+            jsonschema::validator_for(&schema).expect("Invalid weather response schema");
+        let errors: Vec<String> = validator
+            .iter_errors(value)
+            .map(|err| format!("{} at {}", err, err.instance_path()))
+            .collect();
+        assert!(
+            errors.is_empty(),
+            "Response of {uri} does not match the weather response schema: {errors:#?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_weather_responses_match_schema() {
+        let app = test_router([
+            SoleData::fixture(4804),
+            SoleData {
+                min_temp: None,
+                pressure: None,
+                atmo_opacity: None,
+                sunrise: None,
+                sunset: None,
+                ..SoleData::fixture(4802)
+            },
+            // Landing sol
+            SoleData {
+                terrestrial_date: NaiveDate::from_ymd_opt(2012, 8, 6).unwrap(),
+                ..SoleData::fixture(0)
+            },
+        ]);
+
+        for uri in [
+            "/weather?date=2026-02-10",
+            "/weather/sol/0",
+            "/weather?date=2026-02-10&units=f",
+            "/weather?date=2026-02-10&compact=false",
+            "/weather?date=2026-02-11&nearest=true",
+            "/weather?date=2026-02-11&nearest=true&compact=false",
+            "/weather/sol/4802",
+            "/weather/sol/4802?compact=false&units=k",
+            "/weather/latest",
+        ] {
+            let (status, body) = get_json(app.clone(), uri).await;
+            assert_eq!(status, StatusCode::OK, "{uri}");
+            assert_matches_weather_schema(uri, &body);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_message_language() {
+        let app = test_router([SoleData::fixture(4804)]);
+        let request = |accept_language: &'static str| {
+            Request::builder()
+                .uri("/weather?date=2099-01-01")
+                .header(header::ACCEPT_LANGUAGE, accept_language)
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        for (accept_language, content_language, message) in [
+            (
+                "nb-NO,en;q=0.5",
+                "nb",
+                "Datoen er i fremtiden. Det finnes ingen værdata for den ennå.",
+            ),
+            (
+                "fr",
+                "en",
+                "Date is in the future. No weather data exists yet.",
+            ),
+        ] {
+            let response = app.clone().oneshot(request(accept_language)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            assert_eq!(
+                response.headers()[header::CONTENT_LANGUAGE],
+                content_language
+            );
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(body["error"], "DATE_IN_FUTURE");
+            assert_eq!(body["message"], message);
+        }
+
+        let nb_request = |uri: &str| {
+            Request::builder()
+                .uri(uri)
+                .header(header::ACCEPT_LANGUAGE, "nb")
+                .body(Body::empty())
+                .unwrap()
+        };
+        for (uri, content_language, message) in [
+            (
+                "/weather/compare?date1=2026-02-10",
+                "nb",
+                "Send forespørselen med parameterne ?date1=<første dato>&date2=<andre dato>.",
+            ),
+            (
+                "/convert",
+                "nb",
+                "Send forespørselen med parameteren ?date=<dato> eller ?sol=<sol>.",
+            ),
+            (
+                &format!("/weather?date={}", "1".repeat(MAX_DATE_LEN + 1)),
+                "nb",
+                "Ugyldig dato. Datoen kan være på høyst 64 tegn.",
+            ),
+            // Messages without a translation stay in English
+            (
+                "/weather?date=2026-02-10&rover=curiosity",
+                "en",
+                "Unknown rover 'curiosity'. Available rovers are msl.",
+            ),
+            (
+                "/weather/sol/9000",
+                "en",
+                "No weather data found for sol 9000. Cached sols of rover msl range from 4804 to 4804.",
+            ),
+        ] {
+            let response = app.clone().oneshot(nb_request(uri)).await.unwrap();
+            assert_eq!(
+                response.headers()[header::CONTENT_LANGUAGE],
+                content_language,
+                "{uri}"
+            );
+            assert!(
+                response
+                    .headers()
+                    .get_all(header::VARY)
+                    .iter()
+                    .any(|vary| vary == "accept-language"),
+                "{uri}"
+            );
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(body["message"], message, "{uri}");
+        }
+    }
+
+    /// Every error message with a Norwegian entry in the catalog, rendered the way handlers build them. Rewording a
+    /// message or changing a constant it contains fails here instead of silently dropping the translation.
+    #[tokio::test]
+    async fn test_error_messages_have_norwegian_translations() {
+        let messages = [
+            DateError::InFuture.to_string(),
+            DateError::BeforeLanding.to_string(),
+            parse_valid_date("not a date").unwrap_err().to_string(),
+            parse_valid_date(&"1".repeat(MAX_DATE_LEN + 1))
+                .unwrap_err()
+                .to_string(),
+            "x".parse::<TemperatureUnit>().unwrap_err().to_string(),
+            negotiate(Some("yaml"), &HeaderMap::new())
+                .unwrap_err()
+                .to_string(),
+        ];
+        for message in messages {
+            assert!(
+                language::translate(&message, Language::Norwegian).is_some(),
+                "Missing Norwegian translation of {message:?}"
+            );
+        }
+
+        let app = test_router([
+            SoleData::fixture(4804),
+            SoleData {
+                sunrise: None,
+                ..SoleData::fixture(4803)
+            },
+            SoleData::fixture(4801),
+        ]);
+        for uri in [
+            "/weather/kelvin",
+            "/weather/compare?date1=2026-02-10",
+            "/convert",
+            "/weather?from=2026-02-10&to=2026-02-01",
+            "/weather/sol/4802",
+            "/weather/daylight?date=2026-02-09",
+        ] {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .uri(uri)
+                        .header(header::ACCEPT_LANGUAGE, "nb")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert!(!response.status().is_success(), "{uri}");
+            assert_eq!(response.headers()[header::CONTENT_LANGUAGE], "nb", "{uri}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_weather_compare() {
+        let app = test_router([
+            SoleData::fixture(4804),
+            SoleData {
+                min_temp: Some(-72),
+                max_temp: None,
+                ..SoleData::fixture(4805)
+            },
+        ]);
+
+        let (status, body) = get_json(
+            app.clone(),
+            "/weather/compare?date1=2026-02-10&date2=2026-02-11&units=f",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["sol1"], 4804);
+        assert_eq!(body["sol2"], 4805);
+        assert_eq!(body["date1"]["min_temp"], "-101.2");
+        assert_eq!(body["date2"]["min_temp"], "-97.6");
+        assert_eq!(body["min_temp_delta"], 3.6);
+        assert_eq!(body["max_temp_delta"], serde_json::Value::Null);
+        assert_eq!(body["unit"], "f");
+
+        let (status, body) = get_json(
+            app.clone(),
+            "/weather/compare?date1=2026-01-01&date2=2026-02-10",
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["date1"], serde_json::Value::Null);
+        assert_eq!(body["date2"]["martian_sol_day"], "4804");
+        assert_eq!(body["min_temp_delta"], serde_json::Value::Null);
+
+        let (status, body) = get_json(app, "/weather/compare?date1=2026-02-10").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "MISSING_DATE");
+    }
+
+    #[tokio::test]
+    async fn test_weather_etag_per_representation() {
+        let app = test_router([SoleData::fixture(4804)]);
+        let request = |uri: &str, accept: &str, if_none_match: Option<&str>| {
+            let mut request = Request::builder().uri(uri).header(header::ACCEPT, accept);
+            if let Some(etag) = if_none_match {
+                request = request.header(header::IF_NONE_MATCH, etag);
+            }
+            request.body(Body::empty()).unwrap()
+        };
+        let etag_of = |response: &Response| response.headers()[header::ETAG].clone();
+
+        let csv = app
+            .clone()
+            .oneshot(request("/weather?date=2026-02-10", "text/csv", None))
+            .await
+            .unwrap();
+        assert!(
+            csv.headers()
+                .get_all(header::VARY)
+                .iter()
+                .any(|vary| vary == "accept")
+        );
+        let csv_etag = etag_of(&csv);
+        let csv_etag = csv_etag.to_str().unwrap();
+
+        let json = app
+            .clone()
+            .oneshot(request(
+                "/weather?date=2026-02-10",
+                "application/json",
+                Some(csv_etag),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(json.status(), StatusCode::OK);
+        assert_ne!(etag_of(&json), csv_etag);
+
+        let pretty = app
+            .clone()
+            .oneshot(request(
+                "/weather?date=2026-02-10&pretty=true",
+                "application/json",
+                None,
+            ))
+            .await
+            .unwrap();
+        assert_ne!(etag_of(&pretty), etag_of(&json));
+
+        let not_modified = app
+            .oneshot(request(
+                "/weather?date=2026-02-10",
+                "text/csv",
+                Some(csv_etag),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(not_modified.status(), StatusCode::NOT_MODIFIED);
+    }
+
+    #[tokio::test]
+    async fn test_weather_extremes() {
+        let app = test_router([
+            SoleData::fixture(4803),
+            SoleData {
+                min_temp: Some(-80),
+                max_temp: None,
+                ..SoleData::fixture(4804)
+            },
+            SoleData {
+                min_temp: None,
+                max_temp: Some(-2),
+                ..SoleData::fixture(4805)
+            },
+        ]);
+
+        let (status, body) = get_json(app.clone(), "/weather/extremes?n=1&units=k").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["coldest"].as_array().unwrap().len(), 1);
+        assert_eq!(body["coldest"][0]["martian_sol_day"], "4804");
+        assert_eq!(body["coldest"][0]["terrestrial_date"], "2026-02-10");
+        assert_eq!(body["coldest"][0]["min_temp"], "193.2");
+        assert_eq!(body["coldest"][0]["max_temp"], "N/A");
+        assert_eq!(body["warmest"][0]["martian_sol_day"], "4805");
+        assert_eq!(body["units"], "k");
+
+        let (status, body) = get_json(app.clone(), "/weather/extremes").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["coldest"].as_array().unwrap().len(), 2);
+        assert_eq!(body["warmest"][1]["martian_sol_day"], "4803");
+
+        for uri in ["/weather/extremes?n=0", "/weather/extremes?n=51"] {
+            let (status, body) = get_json(app.clone(), uri).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+            assert_eq!(body["error"], "INVALID_N");
+        }
+
+        let (status, body) = get_json(test_router([]), "/weather/extremes").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "NO_DATA");
+    }
+
+    #[tokio::test]
+    async fn test_weather_compact_false() {
+        let app = test_router([SoleData {
+            min_temp: None,
+            pressure: None,
+            ..SoleData::fixture(4804)
+        }]);
+
+        let (_, body) = get_json(app.clone(), "/weather?date=2026-02-10").await;
+        assert_eq!(body["min_temp"], "N/A");
+        assert!(body.get("sol_delta").is_none());
+
+        for uri in [
+            "/weather?date=2026-02-10&compact=false",
+            "/weather/sol/4804?compact=false",
+        ] {
+            let (status, body) = get_json(app.clone(), uri).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(body["min_temp"], serde_json::Value::Null);
+            assert_eq!(body["pressure"], serde_json::Value::Null);
+            assert_eq!(body["max_temp"], "-7");
+            for field in ["requested_sol", "returned_sol", "sol_delta"] {
+                assert_eq!(body.get(field), Some(&serde_json::Value::Null), "{field}");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_weather_head() {
+        let app = test_router([SoleData::fixture(4804)]);
+
+        for (uri, expected) in [
+            ("/weather?date=2026-02-10", StatusCode::OK),
+            ("/weather?date=2026-02-11", StatusCode::NOT_FOUND),
+        ] {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .method(axum::http::Method::HEAD)
+                        .uri(uri)
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+
+            assert_eq!(response.status(), expected);
+            assert!(response.headers().contains_key(X_DATA_UPDATED_AT));
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            assert!(body.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_max_sols_per_response() {
+        let app = test_router_with_config(
+            Config {
+                max_sols_per_response: 5,
+                ..Config::fixture()
+            },
+            [SoleData::fixture(4804)],
+        );
+
+        let (status, body) = get_json(app.clone(), "/weather?week=2026-W07").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "TOO_MANY_SOLS");
+        assert_eq!(
+            body["message"],
+            "Response would contain 7 sols. Maximum allowed is 5 sols per response."
+        );
+
+        let (status, body) =
+            get_json(app.clone(), "/weather/window?date=2026-02-10&radius=3").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "TOO_MANY_SOLS");
+
+        let (status, _) = get_json(app, "/weather/window?date=2026-02-10&radius=2").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_weather_rejects_earth_tz() {
+        let app = test_router([SoleData::fixture(4804)]);
+
+        let (status, body) = get_json(
+            app,
+            "/weather/daylight?date=2026-02-10&earth_tz=Europe/Oslo",
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "EARTH_TZ_UNSUPPORTED");
+        assert!(
+            body["message"]
+                .as_str()
+                .unwrap()
+                .contains("Mars local mean solar time")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_strict_query() {
+        let app = test_router([SoleData::fixture(4804)]);
+
+        let (status, _) = get_json(app.clone(), "/weather?date=2026-02-10&unit=f").await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, body) =
+            get_json(app.clone(), "/weather?date=2026-02-10&unit=f&strict=true").await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["error"], "UNKNOWN_PARAMETERS");
+        assert_eq!(body["message"], "Unknown query parameters: unit.");
+
+        let (status, _) = get_json(app, "/weather?date=2026-02-10&units=f&strict=true").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_hello_lists_routes() {
+        let response = test_router([])
+            .oneshot(Request::builder().uri("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(body.contains("<li>POST /weather/batch"));
+        for path in ["/sols", "/health", "/convert", "/raw/history"] {
+            assert!(body.contains(&format!("<li>GET {path}")), "Missing {path}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hello_json() {
+        let response = test_router([])
+            .oneshot(
+                Request::builder()
+                    .uri("/")
+                    .header(header::ACCEPT, "application/json")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        assert_eq!(response.headers()[header::VARY], "accept");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(body["name"], "linx");
+        assert_eq!(body["openapi"], "/openapi.json");
+        assert_eq!(body["docs"], "/docs");
+        let routes = body["routes"].as_array().unwrap();
+        assert!(
+            routes
+                .iter()
+                .any(|route| { route["method"] == "POST" && route["path"] == "/weather/batch" })
+        );
+        assert!(
+            routes
+                .iter()
+                .any(|route| route["path"] == "/weather/extremes")
+        );
+
+        let response = test_router([])
+            .oneshot(
+                Request::builder()
+                    .uri("/")
+                    .header(header::ACCEPT, "text/html,application/xml;q=0.9,*/*;q=0.8")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert!(
+            response.headers()[header::CONTENT_TYPE]
+                .to_str()
+                .unwrap()
+                .starts_with("text/html")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rover_defaults_to_first_feed_category() {
+        let config = Config {
+            feed_categories: vec!["insight_temperature".to_string(), "msl".to_string()],
+            ..Config::fixture()
+        };
+        let app = test_router_with_config(config, [SoleData::fixture(4804)]);
+
+        let (status, body) = get_json(app.clone(), "/sols").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["count"], 0);
+        let (status, body) = get_json(app.clone(), "/sols?rover=msl").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["count"], 1);
+
+        let (status, body) = get_json(app.clone(), "/weather?date=2026-02-10").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "NO_DATA");
+        let (status, body) =
+            get_json(app.clone(), "/weather/kelvin?date=2026-02-10&rover=msl").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["martian_sol_day"], "4804");
+
+        let (status, _) = get_json(app.clone(), "/sol/now").await;
+        assert_eq!(status, StatusCode::OK);
+        for uri in [
+            "/sols?rover=curiosity",
+            "/sol/now?rover=curiosity",
+            "/weather/kelvin?date=2026-02-10&rover=curiosity",
+        ] {
+            let (status, body) = get_json(app.clone(), uri).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{uri}");
+            assert_eq!(body["error"], "UNKNOWN_ROVER");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_docs_loads_pinned_assets() {
+        let response = test_router([])
+            .oneshot(Request::builder().uri("/docs").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("https://unpkg.com/swagger-ui-dist@5.17.14/swagger-ui-bundle.js"));
+
+        let config = Config {
+            swagger_ui_url: "/static/swagger-ui".to_string(),
+            ..Config::fixture()
+        };
+        let response = test_router_with_config(config, [])
+            .oneshot(Request::builder().uri("/docs").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains(r#"href="/static/swagger-ui/swagger-ui.css""#));
+        assert!(!body.contains("unpkg.com"));
+    }
+
+    #[tokio::test]
+    async fn test_raw_history() {
+        let config = Config {
+            raw_history_size: 2,
+            ..Config::fixture()
+        };
+        let state = test_state(config, []);
+        for sol_count in 1..=3 {
+            state.raw_history.push(RawHistoryEntry {
+                rover: "msl".to_string(),
+                fetched_at: chrono::Utc::now(),
+                sol_count,
+                payload: serde_json::json!({ "soles": [] }),
+            });
+        }
+
+        let (status, body) = get_json(build_app(state.clone()), "/raw/history").await;
+        assert_eq!(status, StatusCode::OK);
+        let sol_counts: Vec<_> = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["sol_count"].as_u64().unwrap())
+            .collect();
+        assert_eq!(sol_counts, vec![3, 2]);
+        assert_eq!(body[0]["payload"], serde_json::json!({ "soles": [] }));
+
+        let (status, body) = get_json(build_app(state), "/raw/history?rover=curiosity").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "UNKNOWN_ROVER");
+    }
+
+    #[tokio::test]
+    async fn test_health_reports_refreshes() {
+        let state = test_state(Config::fixture(), [SoleData::fixture(4804)]);
+        state.metrics.record_refresh_success();
+        state.metrics.record_refresh_failure();
+        state.metrics.record_refresh_failure();
+
+        let (status, body) = get_json(build_app(state), "/health").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["soles_count"], 1);
+        assert_eq!(body["refreshes_succeeded"], 1);
+        assert_eq!(body["refreshes_failed"], 2);
+        assert_eq!(body["updater_restarts"], 0);
+        assert_eq!(body["last_updater_restart"], serde_json::Value::Null);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited_requests_are_counted() {
+        let config = Config {
+            rate_limit_per_minute: 1,
+            ..Config::fixture()
+        };
+        let state = test_state(config, [SoleData::fixture(4804)]);
+        let app = build_app(state.clone());
+        let client = ConnectInfo(SocketAddr::from(([203, 0, 113, 7], 4000)));
+
+        let mut statuses = Vec::new();
+        for _ in 0..2 {
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .uri("/weather?date=2026-02-10")
+                        .extension(client)
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            statuses.push(response.status());
+        }
+
+        assert_eq!(statuses, [StatusCode::OK, StatusCode::TOO_MANY_REQUESTS]);
+        let rendered = state.metrics.render(1, 0);
+        assert!(rendered.contains("linx_weather_requests_total 2\n"));
+        assert!(rendered.contains("linx_weather_responses_total{status=\"429\"} 1\n"));
+    }
+
+    async fn post_admin_refresh(
+        app: Router,
+        token: Option<&str>,
+    ) -> (StatusCode, serde_json::Value) {
+        let mut request = Request::builder()
+            .method(axum::http::Method::POST)
+            .uri("/admin/refresh");
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {token}"));
+        }
+        let response = app
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_admin_refresh_requires_token() {
+        let (status, body) = post_admin_refresh(test_router([]), Some("s3cret")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"], "UNAUTHORIZED");
+
+        let config = Config {
+            admin_token: Some("s3cret".to_string()),
+            ..Config::fixture()
+        };
+        for token in [None, Some("wrong")] {
+            let app = test_router_with_config(config.clone(), []);
+            let (status, _) = post_admin_refresh(app, token).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    // Talks to a local server, which the bundled fixtures replace when mock-data is enabled
+    #[cfg(not(feature = "mock-data"))]
+    #[tokio::test]
+    async fn test_admin_refresh() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let feed_url = format!("http://{}/", listener.local_addr().unwrap());
+        let app = Router::new().route(
+            "/",
+            get(|| async {
+                Json(serde_json::json!({
+                    "soles": [
+                        {
+                            "id": "4804", "terrestrial_date": "2026-02-10", "sol": "4804",
+                            "min_temp": "-74", "max_temp": "-7", "sunrise": "05:19", "sunset": "17:22"
+                        },
+                        {
+                            "id": "4805", "terrestrial_date": "2026-02-11", "sol": "4805",
+                            "min_temp": "-75", "max_temp": "-8", "sunrise": "05:19", "sunset": "17:22"
+                        }
+                    ]
+                }))
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        let config = Config {
+            feed_url,
+            admin_token: Some("s3cret".to_string()),
+            ..Config::fixture()
+        };
+        let app = test_router_with_config(config, [SoleData::fixture(4804)]);
+
+        let (status, body) = post_admin_refresh(app.clone(), Some("s3cret")).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["soles_count"], 2);
+        assert_eq!(body["failed_rovers"], serde_json::json!([]));
+        let (status, _) = get_json(app, "/weather/sol/4805").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_not_found() {
+        let (status, body) = get_json(test_router([]), "/wether?date=2026-02-10").await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "NOT_FOUND");
+        let endpoints = body["endpoints"].as_array().unwrap();
+        for endpoint in ["/", "/weather", "/sols", "/health", "/docs"] {
+            assert!(endpoints.contains(&serde_json::json!(endpoint)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_weather_range_require_temps() {
+        let app = test_router([
+            SoleData::fixture(4803),
+            SoleData {
+                min_temp: None,
+                ..SoleData::fixture(4804)
+            },
+        ]);
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/weather?from=2026-02-09&to=2026-02-11&require_temps=true")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[X_FILTERED_SOLS], "2");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let entries: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0]["martian_sol_day"], "4803");
+    }
+
+    #[test]
+    fn test_parse_iso_week() {
+        assert_eq!(
+            parse_iso_week("2026-W07").unwrap(),
+            (
+                NaiveDate::from_ymd_opt(2026, 2, 9).unwrap(),
+                NaiveDate::from_ymd_opt(2026, 2, 15).unwrap()
+            )
+        );
+        // ISO week 1 of 2026 starts in 2025
+        assert_eq!(
+            parse_iso_week("2026-W01").unwrap().0,
+            NaiveDate::from_ymd_opt(2025, 12, 29).unwrap()
+        );
+        assert!(parse_iso_week("2026-W53").is_ok());
+        assert!(parse_iso_week("2025-W53").is_err());
+        assert!(parse_iso_week("2026-W00").is_err());
+        assert!(parse_iso_week("2026-07").is_err());
+        assert!(parse_iso_week("2026-W7x").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_weather_by_week() {
+        let app = test_router([SoleData::fixture(4803), SoleData::fixture(4804)]);
+
+        let (status, body) = get_json(app.clone(), "/weather?week=2026-W07").await;
+        assert_eq!(status, StatusCode::OK);
+        let sols: Vec<&str> = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["martian_sol_day"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            sols,
+            ["4803", "4804", "4805", "4806", "4807", "4808", "4809"]
+        );
+
+        let (status, body) = get_json(app, "/weather?week=2026-02-09").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "INVALID_WEEK");
+    }
+
+    #[tokio::test]
+    async fn test_weather_rejects_long_date() {
+        let app = test_router([SoleData::fixture(4804)]);
+
+        let date = format!("2026-02-10T00:00:00{}Z", "0".repeat(MAX_DATE_LEN));
+        let (status, body) = get_json(app.clone(), &format!("/weather?date={date}")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "INVALID_DATE_FORMAT");
+        assert_eq!(
+            body["message"],
+            "Invalid date. Date must be at most 64 characters long."
+        );
+
+        let (status, body) = get_json(app.clone(), &format!("/convert?date={date}")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body["message"],
+            "Invalid date. Date must be at most 64 characters long."
+        );
+
+        let (status, _) =
+            get_json(app, "/weather?date=2026-02-10T00:00:00.123456789%2B01:00").await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[test]
+    fn test_parse_date_from_string() {
+        assert_eq!(
+            parse_date_from_string("2026-02-09").unwrap(),
+            DateTime::parse_from_rfc3339("2026-02-09T00:00:00Z").unwrap()
+        );
+        // Offsets are converted to UTC, which can change the date
+        assert_eq!(
+            parse_date_from_string("2026-02-09T00:42:00+01:00").unwrap(),
+            DateTime::parse_from_rfc3339("2026-02-08T23:42:00Z").unwrap()
+        );
+        assert_eq!(
+            parse_date_from_string("2026-02-09T20:42:00Z").unwrap(),
+            DateTime::parse_from_rfc3339("2026-02-09T20:42:00Z").unwrap()
+        );
+
+        // Date times without offset are taken as UTC
+        assert_eq!(
+            parse_date_from_string("2026-02-15T12:00:00").unwrap(),
+            DateTime::parse_from_rfc3339("2026-02-15T12:00:00Z").unwrap()
+        );
+
+        for invalid in [
+            "yesterday",
+            "",
+            "2026-02-30",
+            "2026-02-09T25:00:00Z",
+            "2026-02-15T12:00:00 ",
+            "2026-02-15T12:00",
+        ] {
+            let err = parse_date_from_string(invalid).unwrap_err();
+            assert!(
+                err.to_string().starts_with("Invalid format for date."),
+                "Unexpected error for {invalid:?}: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_date_from_alternative_formats() {
+        let expected = DateTime::parse_from_rfc3339("2026-02-15T00:00:00Z").unwrap();
+        assert_eq!(parse_date_from_string("2026-02-15").unwrap(), expected);
+        assert_eq!(parse_date_from_string("2026/02/15").unwrap(), expected);
+        assert_eq!(parse_date_from_string("15-02-2026").unwrap(), expected);
+
+        // Ambiguous input is read as %Y-%m-%d
+        assert_eq!(
+            parse_date_from_string("10-02-03").unwrap(),
+            DateTime::parse_from_rfc3339("0010-02-03T00:00:00Z").unwrap()
+        );
+        // US dates are not supported
+        assert!(parse_date_from_string("02/15/2026").is_err());
+        assert!(parse_date_from_string("15/02/2026").is_err());
+
+        assert_eq!(
+            parse_date_from_string("2026-02-15T20:42:00Z").unwrap(),
+            DateTime::parse_from_rfc3339("2026-02-15T20:42:00Z").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_date_from_epoch_seconds() {
+        assert_eq!(
+            parse_date_from_string("1770681600").unwrap(),
+            DateTime::parse_from_rfc3339("2026-02-10T00:00:00Z").unwrap()
+        );
+        assert!(parse_date_from_string("-1770681600").is_err());
+        assert!(parse_date_from_string("99999999999999999999").is_err());
+        assert!(parse_date_from_string("999999999999999999").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_cached_soles_data_latest() {
+        let soles_data = [4803, 4805, 4804]
+            .into_iter()
+            .map(|sol| (("msl".to_string(), Sole(sol)), SoleData::fixture(sol)))
+            .chain([(("m2020".to_string(), Sole(1700)), SoleData::fixture(1700))])
+            .collect();
+        let cache = CachedSolesData::new(soles_data, HashMap::new(), HashMap::new());
+
+        assert_eq!(
+            cache.latest("msl").await.map(|data| data.sol),
+            Some(Sole(4805))
+        );
+        assert_eq!(
+            cache.latest("m2020").await.map(|data| data.sol),
+            Some(Sole(1700))
+        );
+        assert!(cache.latest("insight").await.is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_cached_soles_data_update_is_atomic() {
+        fn fetched(count: i64) -> FetchedSoles {
+            FetchedSoles {
+                soles: (1..=count)
+                    .map(|sol| (("msl".to_string(), Sole(sol)), SoleData::fixture(sol)))
+                    .collect(),
+                raw: serde_json::Value::Null,
+                anomalies: 0,
+                validators: FeedValidators::default(),
+            }
+        }
+
+        let cache = Arc::new(CachedSolesData::new(
+            fetched(1).soles,
+            HashMap::new(),
+            HashMap::new(),
+        ));
+
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let cache = cache.clone();
+                tokio::spawn(async move {
+                    // Every soles count must always be seen with the timestamp of the update that set it
+                    let mut seen = HashMap::new();
+                    for _ in 0..2000 {
+                        let status = cache.status(Duration::from_secs(3600)).await;
+                        let updated_at =
+                            *seen.entry(status.soles_count).or_insert(status.updated_at);
+                        assert_eq!(updated_at, status.updated_at);
+                        tokio::task::yield_now().await;
+                    }
+                })
+            })
+            .collect();
+
+        for count in 2..200 {
+            cache.update("msl", fetched(count)).await;
+            tokio::task::yield_now().await;
+        }
+
+        for reader in readers {
+            reader.await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_is_unaffected_by_update() {
+        let soles_data = [(("msl".to_string(), Sole(4804)), SoleData::fixture(4804))]
+            .into_iter()
+            .collect();
+        let cache = CachedSolesData::new(soles_data, HashMap::new(), HashMap::new());
+        let before = cache.snapshot().await;
+
+        cache
+            .update(
+                "msl",
+                FetchedSoles {
+                    soles: [(("msl".to_string(), Sole(4805)), SoleData::fixture(4805))]
+                        .into_iter()
+                        .collect(),
+                    raw: serde_json::Value::Null,
+                    anomalies: 0,
+                    validators: FeedValidators::default(),
+                },
+            )
+            .await;
+        let after = cache.snapshot().await;
+
+        assert!(before.get("msl", 4804).is_some());
+        assert!(before.get("msl", 4805).is_none());
+        assert!(after.get("msl", 4804).is_none());
+        assert!(after.get("msl", 4805).is_some());
+        assert!(after.updated_at >= before.updated_at);
+    }
+
+    #[tokio::test]
+    async fn test_mark_checked_keeps_cached_data() {
+        let soles_data = [(("msl".to_string(), Sole(4804)), SoleData::fixture(4804))]
+            .into_iter()
+            .collect();
+        let cache = CachedSolesData::new(soles_data, HashMap::new(), HashMap::new());
+        let before = cache.status(Duration::from_secs(3600)).await;
+
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        cache.mark_checked().await;
+        let after = cache.status(Duration::from_secs(3600)).await;
+
+        assert_eq!(after.updated_at, before.updated_at);
+        assert!(after.checked_at > before.checked_at);
+        assert_eq!(after.soles_count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_weather_json_memo_is_cleared_on_update() {
+        let soles_data = [(("msl".to_string(), Sole(4804)), SoleData::fixture(4804))]
+            .into_iter()
+            .collect();
+        let cache = CachedSolesData::new(soles_data, HashMap::new(), HashMap::new());
+
+        let json = cache
+            .weather_json("msl", 4804, TemperatureUnit::Celsius)
+            .await
+            .unwrap()
+            .unwrap();
+        let memoized = cache
+            .weather_json("msl", 4804, TemperatureUnit::Celsius)
+            .await
+            .unwrap()
+            .unwrap();
+        // Served from the memo without serializing again
+        assert_eq!(json.as_ptr(), memoized.as_ptr());
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&json).unwrap()["min_temp"],
+            "-74"
+        );
+
+        cache
+            .update(
+                "msl",
+                FetchedSoles {
+                    soles: [(
+                        ("msl".to_string(), Sole(4804)),
+                        SoleData {
+                            min_temp: Some(-80),
+                            ..SoleData::fixture(4804)
+                        },
+                    )]
+                    .into_iter()
+                    .collect(),
+                    raw: serde_json::Value::Null,
+                    anomalies: 0,
+                    validators: FeedValidators::default(),
+                },
+            )
+            .await;
+
+        let updated = cache
+            .weather_json("msl", 4804, TemperatureUnit::Celsius)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&updated).unwrap()["min_temp"],
+            "-80"
+        );
+        assert!(
+            cache
+                .weather_json("msl", 4805, TemperatureUnit::Celsius)
+                .await
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_secs_until_refresh() {
+        let updated_at = chrono::Utc::now();
+        let refresh_interval = Duration::from_secs(3600);
+
+        assert_eq!(
+            secs_until_refresh(updated_at, refresh_interval, updated_at),
+            3600
+        );
+        assert_eq!(
+            secs_until_refresh(
+                updated_at,
+                refresh_interval,
+                updated_at + chrono::Duration::seconds(1200)
+            ),
+            2400
+        );
+        // Overdue refreshes are clamped
+        assert_eq!(
+            secs_until_refresh(
+                updated_at,
+                refresh_interval,
+                updated_at + chrono::Duration::seconds(5000)
+            ),
+            0
+        );
+        // Clock skew should not extend the max age
+        assert_eq!(
+            secs_until_refresh(
+                updated_at,
+                refresh_interval,
+                updated_at - chrono::Duration::seconds(10)
+            ),
+            3600
+        );
+    }
+
+    #[test]
+    fn test_is_older_than() {
+        let updated_at = chrono::Utc::now();
+        let max_age = Duration::from_secs(3600);
+
+        assert!(!is_older_than(updated_at, max_age, updated_at));
+        assert!(!is_older_than(
+            updated_at,
+            max_age,
+            updated_at + chrono::Duration::seconds(3600)
+        ));
+        assert!(is_older_than(
+            updated_at,
+            max_age,
+            updated_at + chrono::Duration::seconds(3601)
+        ));
+        // Clock skew should not mark data as stale
+        assert!(!is_older_than(
+            updated_at,
+            max_age,
+            updated_at - chrono::Duration::seconds(10)
+        ));
+    }
+
+    #[test]
+    fn test_is_stale() {
+        let checked_at = chrono::Utc::now();
+        let refresh_interval = Duration::from_secs(3600);
+
+        // Refresh due but still in progress
+        assert!(!is_stale(
+            checked_at,
+            refresh_interval,
+            checked_at + chrono::Duration::minutes(70)
+        ));
+        assert!(is_stale(
+            checked_at,
+            refresh_interval,
+            checked_at + chrono::Duration::minutes(91)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_no_stale_warning_while_refreshing() {
+        // Every request is past the refresh interval, as if the updater were still fetching
+        let config = Config {
+            refresh_interval: Duration::ZERO,
+            ..Config::fixture()
+        };
+        let response = test_router_with_config(config, [SoleData::fixture(4804)])
+            .oneshot(
+                Request::builder()
+                    .uri("/weather?date=2026-02-10")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(header::WARNING).is_none());
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "max-age=0");
+    }
+
+    #[test]
+    fn test_truncate_for_log() {
+        assert_eq!(truncate_for_log("{\"soles\":[]}"), "{\"soles\":[]}");
+
+        let long = "é".repeat(LOG_SNIPPET_CHARS + 1);
+        let truncated = truncate_for_log(&long);
+        assert!(truncated.starts_with(&"é".repeat(LOG_SNIPPET_CHARS)));
+        assert!(truncated.ends_with(&format!("... ({} bytes total)", long.len())));
+    }
+
+    #[test]
+    fn test_parse_sole_rows_skips_malformed_rows() {
+        let raw = serde_json::json!({
+            "soles": [
+                {"id":"4804","terrestrial_date":"2026-02-10","sol":"4804","min_temp":"-74",
+                 "max_temp":"-7","sunrise":"05:19","sunset":"17:22"},
+                {"id":"4805","terrestrial_date":"2026-02-11","sol":"4805","min_temp":"-74",
+                 "max_temp":"-7","sunrise":"5pm","sunset":"17:22"}
+            ]
+        });
+
+        let data = serde_path_to_error::deserialize::<_, NasaData>(&raw).unwrap();
+        let soles = parse_sole_rows(&data.soles);
+
+        assert_eq!(soles.len(), 1);
+        assert_eq!(soles[0].sol, Sole(4804));
+    }
+
+    #[test]
+    fn test_dedup_soles() {
+        let without_temps = SoleData {
+            id: "a".to_string(),
+            min_temp: None,
+            max_temp: None,
+            ..SoleData::fixture(4804)
+        };
+        let with_temps = SoleData {
+            id: "b".to_string(),
+            ..SoleData::fixture(4804)
+        };
+        let later_date = SoleData {
+            id: "c".to_string(),
+            terrestrial_date: NaiveDate::from_ymd_opt(2026, 2, 11).unwrap(),
+            ..SoleData::fixture(4804)
+        };
+
+        let id_for = |soles: Vec<SoleData>| {
+            dedup_soles("msl", soles)[&("msl".to_string(), Sole(4804))]
+                .id
+                .clone()
+        };
+
+        assert_eq!(id_for(vec![with_temps.clone(), without_temps.clone()]), "b");
+        assert_eq!(id_for(vec![without_temps, with_temps.clone()]), "b");
+        assert_eq!(id_for(vec![later_date.clone(), with_temps.clone()]), "c");
+        assert_eq!(id_for(vec![with_temps.clone(), later_date]), "c");
+        // Equally complete rows keep the first one
+        let same = SoleData {
+            id: "d".to_string(),
+            ..with_temps.clone()
+        };
+        assert_eq!(id_for(vec![with_temps, same]), "b");
+    }
+
+    #[test]
+    fn test_sole_data_season() {
+        let with_season: SoleData = serde_json::from_str(
+            r#"{"id":"4804","terrestrial_date":"2026-02-10","sol":"4804","season":"Month 4",
+                "min_temp":"-74","max_temp":"-7","sunrise":"05:19","sunset":"17:22"}"#,
+        )
+        .expect("Failed to deserialize row with season");
+        assert_eq!(with_season.season, Some("Month 4".to_string()));
+
+        let without_season: SoleData = serde_json::from_str(
+            r#"{"id":"4804","terrestrial_date":"2026-02-10","sol":"4804",
+                "min_temp":"-74","max_temp":"-7","sunrise":"05:19","sunset":"17:22"}"#,
+        )
+        .expect("Failed to deserialize row without season");
+        assert_eq!(without_season.season, None);
+
+        let empty_season: SoleData = serde_json::from_str(
+            r#"{"id":"4804","terrestrial_date":"2026-02-10","sol":"4804","season":"",
+                "min_temp":"-74","max_temp":"-7","sunrise":"05:19","sunset":"17:22"}"#,
+        )
+        .expect("Failed to deserialize row with empty season");
+        assert_eq!(empty_season.season, None);
+    }
+
+    // Talks to a local server, which the bundled fixtures replace when mock-data is enabled
+    #[cfg(not(feature = "mock-data"))]
+    #[tokio::test]
+    async fn test_fetch_soles_data_retries_timeouts() {
+        // Accepts connections but never responds
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let feed_url = format!("http://{}/", listener.local_addr().unwrap());
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_millis(50))
+            .build()
+            .unwrap();
+
+        let started = std::time::Instant::now();
+        let result = fetch_soles_data(
+            &client,
+            &feed_url,
+            "msl",
+            &FeedValidators::default(),
+            1,
+            Duration::from_millis(10),
+        )
+        .await;
+
+        assert!(result.is_err());
+        // Both attempts should time out
+        assert!(started.elapsed() >= Duration::from_millis(110));
+        drop(listener);
+    }
+
+    #[cfg(not(feature = "mock-data"))]
+    #[tokio::test]
+    async fn test_fetch_soles_data_gives_up_after_retries() {
+        let started = std::time::Instant::now();
+        let result = fetch_soles_data(
+            &reqwest::Client::new(),
+            "http://127.0.0.1:1/",
+            "msl",
+            &FeedValidators::default(),
+            2,
+            Duration::from_millis(10),
+        )
+        .await;
+
+        assert!(result.is_err());
+        // Two retries should wait 10ms + 20ms before giving up
+        assert!(started.elapsed() >= Duration::from_millis(30));
+    }
+
+    #[cfg(not(feature = "mock-data"))]
+    #[tokio::test]
+    async fn test_fetch_soles_data_honors_retry_after() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let feed_url = format!("http://{}/", listener.local_addr().unwrap());
+        let requests = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let app = Router::new().route(
+            "/",
+            get(move || async move {
+                if requests.fetch_add(1, std::sync::atomic::Ordering::Relaxed) == 0 {
+                    (
+                        StatusCode::TOO_MANY_REQUESTS,
+                        [(header::RETRY_AFTER, "0")],
+                        Json(serde_json::json!({})),
+                    )
+                        .into_response()
+                } else {
+                    Json(serde_json::json!({
+                        "soles": [{
+                            "id": "4804", "terrestrial_date": "2026-02-10", "sol": "4804",
+                            "min_temp": "-74", "max_temp": "-7", "sunrise": "05:19", "sunset": "17:22"
+                        }]
+                    }))
+                    .into_response()
+                }
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        // The backoff would wait a minute, Retry-After asks for no wait at all
+        let result = tokio::time::timeout(
+            Duration::from_secs(5),
+            fetch_soles_data(
+                &reqwest::Client::new(),
+                &feed_url,
+                "msl",
+                &FeedValidators::default(),
+                1,
+                Duration::from_secs(60),
+            ),
+        )
+        .await
+        .expect("Retry-After was not honored");
+
+        let fetched = result.unwrap().modified().unwrap();
+        assert_eq!(fetched.soles.len(), 1);
+    }
+
+    #[cfg(not(feature = "mock-data"))]
+    #[tokio::test]
+    async fn test_fetch_soles_data_skips_unchanged_feed() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let feed_url = format!("http://{}/", listener.local_addr().unwrap());
+        let app = Router::new().route(
+            "/",
+            get(|headers: HeaderMap| async move {
+                if headers.get(header::IF_NONE_MATCH).is_some_and(|etag| etag == "\"v1\"") {
+                    return StatusCode::NOT_MODIFIED.into_response();
+                }
+                (
+                    [(header::ETAG, "\"v1\"")],
+                    Json(serde_json::json!({
+                        "soles": [{
+                            "id": "4804", "terrestrial_date": "2026-02-10", "sol": "4804",
+                            "min_temp": "-74", "max_temp": "-7", "sunrise": "05:19", "sunset": "17:22"
+                        }]
+                    })),
+                )
+                    .into_response()
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        let client = reqwest::Client::new();
+
+        let fetched = fetch_soles_data_once(&client, &feed_url, "msl", &FeedValidators::default())
+            .await
+            .unwrap()
+            .modified()
+            .unwrap();
+        assert_eq!(fetched.validators.etag.as_deref(), Some("\"v1\""));
+        assert_eq!(fetched.validators.last_modified, None);
+
+        let outcome = fetch_soles_data_once(&client, &feed_url, "msl", &fetched.validators)
+            .await
+            .unwrap();
+        assert!(matches!(outcome, FetchOutcome::NotModified));
+    }
+
+    #[test]
+    fn test_parse_bundled_fixture() {
+        let data: NasaData = serde_json::from_str(include_str!("../fixtures/msl.json")).unwrap();
+        let soles = parse_sole_rows(&data.soles);
+        assert_eq!(soles.len(), data.soles.len());
+        assert_eq!(soles[0].sol, Sole(4804));
+    }
+
+    #[tokio::test]
+    async fn test_parse_rows_without_sunrise_and_sunset() {
+        let data: NasaData =
+            serde_json::from_str(include_str!("../fixtures/msl_missing_daylight.json")).unwrap();
+        let soles = parse_sole_rows(&data.soles);
+        assert_eq!(soles.len(), 3);
+        assert!(soles.iter().all(|sole| sole.sunset.is_none()));
+        assert_eq!(soles[2].sunrise, NaiveTime::from_hms_opt(5, 17, 0));
+
+        let app = test_router(soles);
+        let (status, body) = get_json(app.clone(), "/weather/sol/4804").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["sunrise"], "N/A");
+        assert_eq!(body["sunset"], "N/A");
+        assert_eq!(body["min_temp"], "-72");
+        assert!(body.get("tz_label").is_none());
+
+        let (status, body) = get_json(app, "/weather/daylight?date=2026-02-10").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"], "NO_DATA");
+    }
+
+    #[cfg(not(feature = "mock-data"))]
+    #[tokio::test]
+    async fn test_fetch_soles_data_rejects_empty_feed() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let feed_url = format!("http://{}/", listener.local_addr().unwrap());
+        let app = Router::new().route(
+            "/",
+            get(|| async { Json(serde_json::json!({ "soles": [] })) }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let result = fetch_soles_data_once(
+            &reqwest::Client::new(),
+            &feed_url,
+            "msl",
+            &FeedValidators::default(),
+        )
+        .await;
+
+        assert_eq!(
+            result.err().map(|err| err.to_string()),
+            Some("Feed msl returned no soles".to_string())
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use tracing_subscriber::EnvFilter;
+
+const DEFAULT_LOG_LEVEL: &str = "info";
+
+/// Initializes logging. The filter is taken from `LINX_LOG_LEVEL`, then `RUST_LOG`, and defaults to `info`.
+/// Logs are JSON objects, one per line, when `LINX_LOG_FORMAT` is `json`.
+pub fn init() {
+    let directives = log_filter(
+        std::env::var("LINX_LOG_LEVEL").ok(),
+        std::env::var("RUST_LOG").ok(),
+    );
+    let (filter, invalid) = match EnvFilter::try_new(&directives) {
+        Ok(filter) => (filter, None),
+        Err(err) => (EnvFilter::new(DEFAULT_LOG_LEVEL), Some(err)),
+    };
+
+    let json = std::env::var("LINX_LOG_FORMAT")
+        .is_ok_and(|format| format.trim().eq_ignore_ascii_case("json"));
+    let builder = tracing_subscriber::fmt().with_env_filter(filter);
+    if json {
+        builder.json().init();
+    } else {
+        builder.init();
+    }
+
+    if let Some(err) = invalid {
+        tracing::warn!("Invalid log filter '{directives}'. Using {DEFAULT_LOG_LEVEL}. Err: {err}");
+    }
+}
+
+/// Picks the first non-empty filter directives of `LINX_LOG_LEVEL` and `RUST_LOG`
+fn log_filter(linx_log_level: Option<String>, rust_log: Option<String>) -> String {
+    [linx_log_level, rust_log]
+        .into_iter()
+        .flatten()
+        .find(|directives| !directives.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_LOG_LEVEL.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_filter() {
+        assert_eq!(log_filter(None, None), "info");
+        assert_eq!(log_filter(None, Some("api=debug".to_string())), "api=debug");
+        assert_eq!(
+            log_filter(Some("warn".to_string()), Some("api=debug".to_string())),
+            "warn"
+        );
+        assert_eq!(
+            log_filter(Some(" ".to_string()), Some("api=debug".to_string())),
+            "api=debug"
+        );
+    }
+}
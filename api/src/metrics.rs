@@ -0,0 +1,173 @@
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use axum::http::StatusCode;
+use chrono::{DateTime, Utc};
+
+/// Counters exposed on /metrics in Prometheus text format
+#[derive(Debug, Default)]
+pub struct Metrics {
+    weather_requests: AtomicU64,
+    weather_not_found_responses: AtomicU64,
+    weather_bad_request_responses: AtomicU64,
+    weather_rate_limited_responses: AtomicU64,
+    upstream_fetch_successes: AtomicU64,
+    upstream_fetch_failures: AtomicU64,
+    refresh_successes: AtomicU64,
+    refresh_failures: AtomicU64,
+    updater_restarts: AtomicU64,
+    last_updater_restart: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl Metrics {
+    pub fn record_weather_response(&self, status: StatusCode) {
+        self.weather_requests.fetch_add(1, Ordering::Relaxed);
+        match status {
+            StatusCode::NOT_FOUND => {
+                self.weather_not_found_responses
+                    .fetch_add(1, Ordering::Relaxed);
+            }
+            StatusCode::BAD_REQUEST => {
+                self.weather_bad_request_responses
+                    .fetch_add(1, Ordering::Relaxed);
+            }
+            StatusCode::TOO_MANY_REQUESTS => {
+                self.weather_rate_limited_responses
+                    .fetch_add(1, Ordering::Relaxed);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn record_upstream_fetch<T, E>(&self, result: &Result<T, E>) {
+        match result {
+            Ok(_) => self
+                .upstream_fetch_successes
+                .fetch_add(1, Ordering::Relaxed),
+            Err(_) => self.upstream_fetch_failures.fetch_add(1, Ordering::Relaxed),
+        };
+    }
+
+    /// Counts a refresh of a rover's soles data by the background updater or /admin/refresh that replaced cached data
+    pub fn record_refresh_success(&self) {
+        self.refresh_successes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a refresh of a rover's soles data by the background updater or /admin/refresh that left cached data as it was
+    pub fn record_refresh_failure(&self) {
+        self.refresh_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the number of succeeded and failed refreshes since startup
+    pub fn refreshes(&self) -> (u64, u64) {
+        (
+            self.refresh_successes.load(Ordering::Relaxed),
+            self.refresh_failures.load(Ordering::Relaxed),
+        )
+    }
+
+    /// Counts a restart of the background updater by the watchdog after it ended unexpectedly
+    pub fn record_updater_restart(&self) {
+        self.updater_restarts.fetch_add(1, Ordering::Relaxed);
+        *self
+            .last_updater_restart
+            .lock()
+            .unwrap_or_else(|err| err.into_inner()) = Some(Utc::now());
+    }
+
+    /// Returns the number of updater restarts since startup and when the last one happened
+    pub fn updater_restarts(&self) -> (u64, Option<DateTime<Utc>>) {
+        (
+            self.updater_restarts.load(Ordering::Relaxed),
+            *self
+                .last_updater_restart
+                .lock()
+                .unwrap_or_else(|err| err.into_inner()),
+        )
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format
+    pub fn render(&self, cached_soles: usize, cache_age_seconds: i64) -> String {
+        let mut out = String::new();
+
+        let _ = writeln!(
+            out,
+            "# HELP linx_weather_requests_total Total number of weather requests.\n\
+             # TYPE linx_weather_requests_total counter\n\
+             linx_weather_requests_total {}",
+            self.weather_requests.load(Ordering::Relaxed)
+        );
+        let _ = writeln!(
+            out,
+            "# HELP linx_weather_responses_total Weather responses by status code.\n\
+             # TYPE linx_weather_responses_total counter\n\
+             linx_weather_responses_total{{status=\"404\"}} {}\n\
+             linx_weather_responses_total{{status=\"400\"}} {}\n\
+             linx_weather_responses_total{{status=\"429\"}} {}",
+            self.weather_not_found_responses.load(Ordering::Relaxed),
+            self.weather_bad_request_responses.load(Ordering::Relaxed),
+            self.weather_rate_limited_responses.load(Ordering::Relaxed)
+        );
+        let _ = writeln!(
+            out,
+            "# HELP linx_upstream_fetches_total Fetches of soles data from NASA by result.\n\
+             # TYPE linx_upstream_fetches_total counter\n\
+             linx_upstream_fetches_total{{result=\"success\"}} {}\n\
+             linx_upstream_fetches_total{{result=\"failure\"}} {}",
+            self.upstream_fetch_successes.load(Ordering::Relaxed),
+            self.upstream_fetch_failures.load(Ordering::Relaxed)
+        );
+        let _ = writeln!(
+            out,
+            "# HELP linx_updater_restarts_total Restarts of the background updater after it ended unexpectedly.\n\
+             # TYPE linx_updater_restarts_total counter\n\
+             linx_updater_restarts_total {}",
+            self.updater_restarts.load(Ordering::Relaxed)
+        );
+        let _ = writeln!(
+            out,
+            "# HELP linx_cached_soles Number of soles in the cache.\n\
+             # TYPE linx_cached_soles gauge\n\
+             linx_cached_soles {cached_soles}"
+        );
+        let _ = writeln!(
+            out,
+            "# HELP linx_cache_age_seconds Seconds since the cache was last updated.\n\
+             # TYPE linx_cache_age_seconds gauge\n\
+             linx_cache_age_seconds {cache_age_seconds}"
+        );
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let metrics = Metrics::default();
+        metrics.record_weather_response(StatusCode::OK);
+        metrics.record_weather_response(StatusCode::NOT_FOUND);
+        metrics.record_weather_response(StatusCode::BAD_REQUEST);
+        metrics.record_weather_response(StatusCode::TOO_MANY_REQUESTS);
+        metrics.record_upstream_fetch::<(), ()>(&Ok(()));
+        metrics.record_upstream_fetch::<(), ()>(&Err(()));
+        metrics.record_upstream_fetch::<(), ()>(&Err(()));
+        metrics.record_updater_restart();
+
+        let rendered = metrics.render(15, 42);
+
+        assert!(rendered.contains("linx_weather_requests_total 4\n"));
+        assert!(rendered.contains("linx_weather_responses_total{status=\"404\"} 1\n"));
+        assert!(rendered.contains("linx_weather_responses_total{status=\"400\"} 1\n"));
+        assert!(rendered.contains("linx_weather_responses_total{status=\"429\"} 1\n"));
+        assert!(rendered.contains("linx_upstream_fetches_total{result=\"success\"} 1\n"));
+        assert!(rendered.contains("linx_upstream_fetches_total{result=\"failure\"} 2\n"));
+        assert!(rendered.contains("linx_updater_restarts_total 1\n"));
+        assert!(rendered.contains("linx_cached_soles 15\n"));
+        assert!(rendered.contains("linx_cache_age_seconds 42\n"));
+    }
+}
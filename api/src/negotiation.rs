@@ -0,0 +1,210 @@
+use anyhow::anyhow;
+use axum::{
+    http::{HeaderMap, HeaderValue, header},
+    response::Response,
+};
+
+/// Representation of responses, chosen by `format` query parameter or `Accept` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseFormat {
+    Json,
+    Csv,
+    Xml,
+}
+
+/// Picks the response format of a request. The `format` query parameter takes precedence over the `Accept` header,
+/// which falls back to JSON when it names no supported format.
+pub fn negotiate(format: Option<&str>, headers: &HeaderMap) -> anyhow::Result<ResponseFormat> {
+    match format.map(str::to_ascii_lowercase).as_deref() {
+        Some("json") => Ok(ResponseFormat::Json),
+        Some("csv") => Ok(ResponseFormat::Csv),
+        Some("xml") => Ok(ResponseFormat::Xml),
+        Some(_) => Err(anyhow!(
+            "Invalid format. Allowed formats are json, csv and xml."
+        )),
+        None => Ok(from_accept(headers)),
+    }
+}
+
+impl ResponseFormat {
+    /// Media types the format is served for
+    fn media_types(&self) -> &'static [&'static str] {
+        match self {
+            ResponseFormat::Json => &["application/json"],
+            ResponseFormat::Csv => &["text/csv"],
+            ResponseFormat::Xml => &["application/xml", "text/xml"],
+        }
+    }
+}
+
+/// Media range of an `Accept` header along with its quality
+struct MediaRange<'a> {
+    range: &'a str,
+    quality: f32,
+}
+
+/// Splits an `Accept` header into its media ranges. A missing header accepts anything.
+fn media_ranges(headers: &HeaderMap) -> Vec<MediaRange<'_>> {
+    let Some(accept) = headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+    else {
+        return vec![MediaRange {
+            range: "*/*",
+            quality: 1.0,
+        }];
+    };
+    accept
+        .split(',')
+        .filter_map(|range| {
+            let mut parts = range.split(';').map(str::trim);
+            let range = parts.next().filter(|range| !range.is_empty())?;
+            let quality = parts
+                .find_map(|param| param.strip_prefix("q="))
+                .map_or(Some(1.0), |quality| quality.parse::<f32>().ok())
+                .unwrap_or(0.0);
+            Some(MediaRange { range, quality })
+        })
+        .collect()
+}
+
+/// Quality of the media type, taken from the most specific range matching it, e.g. `text/csv` over `text/*`
+/// over `*/*`. 0 when no range matches.
+fn quality_of(ranges: &[MediaRange], media_type: &str) -> f32 {
+    let main_type = media_type.split('/').next().unwrap_or_default();
+    ranges
+        .iter()
+        .filter_map(|MediaRange { range, quality }| {
+            let specificity = if range.eq_ignore_ascii_case(media_type) {
+                2
+            } else if range
+                .strip_suffix("/*")
+                .is_some_and(|range| range.eq_ignore_ascii_case(main_type))
+            {
+                1
+            } else if *range == "*/*" {
+                0
+            } else {
+                return None;
+            };
+            Some((specificity, *quality))
+        })
+        .max_by_key(|(specificity, _)| *specificity)
+        .map_or(0.0, |(_, quality)| quality)
+}
+
+/// Picks the format with the quality of the range the client prefers most. JSON is served on ties, e.g. for `*/*`,
+/// and when the client prefers something else, e.g. browsers ranking `text/html` over `application/xml`.
+fn from_accept(headers: &HeaderMap) -> ResponseFormat {
+    let ranges = media_ranges(headers);
+    let preferred = ranges.iter().map(|range| range.quality).fold(0.0, f32::max);
+    [
+        ResponseFormat::Json,
+        ResponseFormat::Csv,
+        ResponseFormat::Xml,
+    ]
+    .into_iter()
+    .find(|format| {
+        let quality = format
+            .media_types()
+            .iter()
+            .map(|media_type| quality_of(&ranges, media_type))
+            .fold(0.0, f32::max);
+        quality > 0.0 && quality == preferred
+    })
+    .unwrap_or(ResponseFormat::Json)
+}
+
+/// Adds `Vary: Accept` to responses of routes negotiating their format, so caches keep each format apart
+pub async fn vary_accept(mut response: Response) -> Response {
+    response
+        .headers_mut()
+        .append(header::VARY, HeaderValue::from_static("accept"));
+    response
+}
+
+/// Whether a route serving HTML by default should serve JSON instead, i.e. the `Accept` header ranks
+/// `application/json` above `text/html`. Browsers rank `text/html` first, so they keep getting HTML.
+pub fn prefers_json(headers: &HeaderMap) -> bool {
+    let ranges = media_ranges(headers);
+    quality_of(&ranges, "application/json") > quality_of(&ranges, "text/html")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn accept(value: &'static str) -> HeaderMap {
+        HeaderMap::from_iter([(header::ACCEPT, HeaderValue::from_static(value))])
+    }
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(
+            negotiate(None, &HeaderMap::new()).unwrap(),
+            ResponseFormat::Json
+        );
+        assert_eq!(
+            negotiate(None, &accept("text/csv")).unwrap(),
+            ResponseFormat::Csv
+        );
+        assert_eq!(
+            negotiate(None, &accept("text/csv;q=0.5, application/xml;q=0.9")).unwrap(),
+            ResponseFormat::Xml
+        );
+        assert_eq!(
+            negotiate(None, &accept("text/xml")).unwrap(),
+            ResponseFormat::Xml
+        );
+        assert_eq!(
+            negotiate(None, &accept("text/html")).unwrap(),
+            ResponseFormat::Json
+        );
+    }
+
+    #[test]
+    fn test_negotiate_defaults_to_json() {
+        for value in [
+            "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8",
+            "text/html, application/xml;q=0.9",
+            "*/*",
+            "application/json, text/csv",
+            "text/csv;q=0, */*",
+            "image/png",
+        ] {
+            assert_eq!(
+                negotiate(None, &accept(value)).unwrap(),
+                ResponseFormat::Json,
+                "{value}"
+            );
+        }
+        assert_eq!(
+            negotiate(None, &accept("text/*, application/json;q=0.5")).unwrap(),
+            ResponseFormat::Csv
+        );
+    }
+
+    #[test]
+    fn test_negotiate_query_overrides_accept() {
+        assert_eq!(
+            negotiate(Some("json"), &accept("text/csv")).unwrap(),
+            ResponseFormat::Json
+        );
+        assert_eq!(
+            negotiate(Some("XML"), &accept("text/csv")).unwrap(),
+            ResponseFormat::Xml
+        );
+        assert!(negotiate(Some("yaml"), &accept("text/csv")).is_err());
+    }
+
+    #[test]
+    fn test_prefers_json() {
+        assert!(prefers_json(&accept("application/json")));
+        assert!(prefers_json(&accept("application/json, */*;q=0.5")));
+        assert!(!prefers_json(&accept(
+            "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8"
+        )));
+        assert!(!prefers_json(&accept("text/html, application/json;q=0.9")));
+        assert!(!prefers_json(&HeaderMap::new()));
+    }
+}
@@ -0,0 +1,208 @@
+use std::sync::Arc;
+
+use axum::{Json, extract::State, response::Html};
+use quick_xml::escape::escape;
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::SharedState;
+use crate::conversion::ConversionInfo;
+use crate::history::RawHistoryEntry;
+use crate::response::{
+    DaylightResponse, ErrorBody, ExtremeSol, KelvinWeatherResponse, SolOutOfRangeBody, Temperature,
+    TemperatureRecord, WeatherBatchEntry, WeatherBatchResult, WeatherCompareResponse,
+    WeatherExtremesResponse, WeatherRangeEntry, WeatherResponse, WeatherStatsResponse,
+    WeatherWindowResponse,
+};
+
+/// OpenAPI spec of all routes, generated from handler annotations
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "linx",
+        description = "Weather on Mars as reported by NASA's rovers"
+    ),
+    paths(
+        crate::hello,
+        crate::weather,
+        crate::weather_by_sol,
+        crate::weather_kelvin,
+        crate::weather_window,
+        crate::weather_latest,
+        crate::weather_daylight,
+        crate::weather_compare,
+        crate::weather_stats,
+        crate::weather_extremes,
+        crate::weather_batch,
+        crate::sols,
+        crate::current_sol,
+        crate::convert,
+        crate::about_conversion,
+        crate::health,
+        crate::prometheus_metrics,
+        crate::raw_payload,
+        crate::raw_history,
+        crate::admin_refresh,
+    ),
+    components(schemas(
+        ErrorBody,
+        SolOutOfRangeBody,
+        WeatherResponse,
+        WeatherRangeEntry,
+        KelvinWeatherResponse,
+        WeatherWindowResponse,
+        DaylightResponse,
+        WeatherCompareResponse,
+        WeatherStatsResponse,
+        WeatherExtremesResponse,
+        ExtremeSol,
+        Temperature,
+        TemperatureRecord,
+        WeatherBatchEntry,
+        WeatherBatchResult,
+        ConversionInfo,
+        RawHistoryEntry,
+        RouteSummary,
+        crate::HelloResponse
+    ))
+)]
+pub struct ApiDoc;
+
+/// Paths of all routes, including the ones serving the spec itself
+pub fn endpoints() -> Vec<String> {
+    let mut paths: Vec<String> = routes().into_iter().map(|route| route.path).collect();
+    paths.dedup();
+    paths
+}
+
+/// Route of the spec along with the first line of its handler's doc comment
+#[derive(Debug, Serialize, ToSchema)]
+pub struct RouteSummary {
+    #[schema(example = "GET")]
+    pub method: &'static str,
+    #[schema(example = "/weather")]
+    pub path: String,
+    pub summary: Option<String>,
+}
+
+/// Routes of the spec, sorted by path, for listing on the landing page
+pub fn routes() -> Vec<RouteSummary> {
+    ApiDoc::openapi()
+        .paths
+        .paths
+        .into_iter()
+        .flat_map(|(path, item)| {
+            [("GET", item.get), ("POST", item.post)]
+                .into_iter()
+                .filter_map(move |(method, operation)| {
+                    operation.map(|operation| RouteSummary {
+                        method,
+                        path: path.clone(),
+                        summary: operation.summary.map(|summary| describe(&summary)),
+                    })
+                })
+        })
+        .chain([
+            RouteSummary {
+                method: "GET",
+                path: "/openapi.json".to_string(),
+                summary: Some("Serves the OpenAPI spec as JSON".to_string()),
+            },
+            RouteSummary {
+                method: "GET",
+                path: "/docs".to_string(),
+                summary: Some("Serves Swagger UI for the OpenAPI spec".to_string()),
+            },
+        ])
+        .collect()
+}
+
+/// Turns a handler doc comment like "Handler that serves ..." into a description like "Serves ..."
+fn describe(summary: &str) -> String {
+    let summary = summary.split_whitespace().collect::<Vec<_>>().join(" ");
+    let summary = summary.strip_prefix("Handler that ").unwrap_or(&summary);
+    let mut chars = summary.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Handler that serves the OpenAPI spec as JSON
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// Handler that serves Swagger UI for the OpenAPI spec. Assets are loaded from `LINX_SWAGGER_UI_URL`, a pinned
+/// release on a CDN by default.
+pub async fn docs(State(state): State<Arc<SharedState>>) -> Html<String> {
+    let assets = escape(state.config.swagger_ui_url.as_str());
+    Html(format!(
+        r##"<!DOCTYPE html>
+<html>
+<head>
+    <title>linx api docs</title>
+    <link rel="stylesheet" href="{assets}/swagger-ui.css" crossorigin="anonymous" />
+</head>
+<body>
+    <div id="swagger-ui"></div>
+    <script src="{assets}/swagger-ui-bundle.js" crossorigin="anonymous"></script>
+    <script>
+        window.onload = () => {{
+            window.ui = SwaggerUIBundle({{ url: "/openapi.json", dom_id: "#swagger-ui" }});
+        }};
+    </script>
+</body>
+</html>"##
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routes() {
+        let routes = routes();
+        let batch = routes
+            .iter()
+            .find(|route| route.path == "/weather/batch")
+            .unwrap();
+        assert_eq!(batch.method, "POST");
+        assert_eq!(
+            batch.summary.as_deref(),
+            Some(
+                "Serves weather data for each date in a JSON array. Invalid dates get an error entry instead of failing the whole batch."
+            )
+        );
+        for path in ["/sols", "/health", "/convert", "/raw/history", "/docs"] {
+            assert!(
+                routes.iter().any(|route| route.path == path),
+                "Missing route {path}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_openapi_spec() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+
+        let weather_params: Vec<&str> = spec["paths"]["/weather"]["get"]["parameters"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|param| param["name"].as_str().unwrap())
+            .collect();
+        for param in ["date", "from", "to", "units", "format"] {
+            assert!(weather_params.contains(&param), "Missing parameter {param}");
+        }
+
+        assert_eq!(
+            spec["paths"]["/weather"]["get"]["responses"]["400"]["content"]["application/json"]["schema"]
+                ["$ref"],
+            "#/components/schemas/ErrorBody"
+        );
+        assert!(spec["paths"]["/weather/sol/{sol}"].is_object());
+        assert!(spec["components"]["schemas"]["WeatherResponse"].is_object());
+    }
+}
@@ -0,0 +1,116 @@
+use axum::{
+    body::Body,
+    extract::{Query, Request},
+    http::header,
+    middleware::Next,
+    response::Response,
+};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct PrettyQuery {
+    pretty: Option<bool>,
+}
+
+/// Middleware that indents JSON responses, both success and error, when requested with `pretty=true`
+pub async fn pretty_json(request: Request, next: Next) -> Response {
+    let pretty = Query::<PrettyQuery>::try_from_uri(request.uri())
+        .is_ok_and(|Query(query)| query.pretty == Some(true));
+
+    let response = next.run(request).await;
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if !pretty || !is_json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let body = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(err) => {
+            tracing::error!("Failed to read JSON response body: {err}");
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+
+    match prettify(&body) {
+        Ok(pretty_body) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Response::from_parts(parts, Body::from(pretty_body))
+        }
+        Err(err) => {
+            tracing::warn!("Failed to indent JSON response: {err}");
+            Response::from_parts(parts, Body::from(body))
+        }
+    }
+}
+
+/// Indents serialized JSON, keeping the order of object keys
+fn prettify(json: &[u8]) -> serde_json::Result<Vec<u8>> {
+    let mut deserializer = serde_json::Deserializer::from_slice(json);
+    let mut out = Vec::new();
+    serde_transcode::transcode(
+        &mut deserializer,
+        &mut serde_json::Serializer::pretty(&mut out),
+    )?;
+    deserializer.end()?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{Json, Router, http::StatusCode, middleware, response::IntoResponse, routing::get};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    #[test]
+    fn test_prettify_keeps_key_order() {
+        let pretty = prettify(br#"{"b":"1","a":[1,2]}"#).unwrap();
+
+        assert_eq!(
+            String::from_utf8(pretty).unwrap(),
+            "{\n  \"b\": \"1\",\n  \"a\": [\n    1,\n    2\n  ]\n}"
+        );
+    }
+
+    async fn body_for(uri: &str) -> String {
+        let app = Router::new()
+            .route(
+                "/",
+                get(|| async {
+                    (
+                        StatusCode::BAD_REQUEST,
+                        Json(serde_json::json!({"error": "INVALID"})),
+                    )
+                        .into_response()
+                }),
+            )
+            .route("/text", get(|| async { "{\"a\":1}" }))
+            .layer(middleware::from_fn(pretty_json));
+
+        let response = app
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(body.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_pretty_json() {
+        assert_eq!(body_for("/").await, r#"{"error":"INVALID"}"#);
+        assert_eq!(body_for("/?pretty=false").await, r#"{"error":"INVALID"}"#);
+        assert_eq!(
+            body_for("/?pretty=true").await,
+            "{\n  \"error\": \"INVALID\"\n}"
+        );
+        assert_eq!(body_for("/text?pretty=true").await, "{\"a\":1}");
+    }
+}
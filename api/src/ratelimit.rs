@@ -0,0 +1,108 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Number of tracked clients after which buckets that have refilled are dropped
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Per client IP token bucket allowing a number of requests per minute
+#[derive(Debug)]
+pub struct RateLimiter {
+    per_minute: u32,
+    buckets: Mutex<HashMap<IpAddr, Bucket>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl RateLimiter {
+    /// Creates a rate limiter. A limit of 0 disables rate limiting.
+    pub fn new(per_minute: u32) -> Self {
+        RateLimiter {
+            per_minute,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.per_minute > 0
+    }
+
+    /// Takes a token for the client, or returns how long until the next one is available
+    pub fn check(&self, ip: IpAddr, now: Instant) -> Result<(), Duration> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+
+        let capacity = self.per_minute as f64;
+        let tokens_per_sec = capacity / 60.0;
+        let mut buckets = self.buckets.lock().unwrap_or_else(|err| err.into_inner());
+
+        if buckets.len() > PRUNE_THRESHOLD {
+            buckets.retain(|_, bucket| {
+                bucket.tokens
+                    + now.duration_since(bucket.refilled_at).as_secs_f64() * tokens_per_sec
+                    < capacity
+            });
+        }
+
+        let bucket = buckets.entry(ip).or_insert(Bucket {
+            tokens: capacity,
+            refilled_at: now,
+        });
+        let elapsed = now.duration_since(bucket.refilled_at).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * tokens_per_sec).min(capacity);
+        bucket.refilled_at = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64(
+                (1.0 - bucket.tokens) / tokens_per_sec,
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        let limiter = RateLimiter::new(2);
+        let client = IpAddr::from([127, 0, 0, 1]);
+        let other_client = IpAddr::from([127, 0, 0, 2]);
+        let now = Instant::now();
+
+        assert!(limiter.check(client, now).is_ok());
+        assert!(limiter.check(client, now).is_ok());
+        assert_eq!(limiter.check(client, now), Err(Duration::from_secs(30)));
+        assert!(limiter.check(other_client, now).is_ok());
+
+        assert!(limiter.check(client, now + Duration::from_secs(30)).is_ok());
+        assert!(
+            limiter
+                .check(client, now + Duration::from_secs(30))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_check_disabled() {
+        let limiter = RateLimiter::new(0);
+        let client = IpAddr::from([127, 0, 0, 1]);
+        let now = Instant::now();
+
+        for _ in 0..100 {
+            assert!(limiter.check(client, now).is_ok());
+        }
+    }
+}
@@ -0,0 +1,947 @@
+use std::{fmt::Display, str::FromStr};
+
+use anyhow::anyhow;
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::{Serialize, Serializer};
+use utoipa::ToSchema;
+
+use crate::SoleData;
+use crate::compact::explicit_nulls;
+use crate::conversion::{SOL_DRIFT_SECS, sol_span};
+use crate::units::TemperatureUnit;
+
+/// Time scale of sunrise and sunset. NASA reports them in local mean solar time at the rover's location.
+pub const MARS_TIME_LABEL: &str = "Mars local mean solar time";
+
+/// Labels sunrise and sunset with their time scale, when the sol has either
+fn tz_label(data: &SoleData) -> Option<&'static str> {
+    (data.sunrise.is_some() || data.sunset.is_some()).then_some(MARS_TIME_LABEL)
+}
+
+/// Weather data for a single sol, shared by all response formats.
+/// Numbers are serialized as strings and missing values as `N/A` to keep the wire format stable,
+/// or as `null` when requested with `compact=false`.
+/// Its JSON shape is pinned by `schemas/weather_response.schema.json`, which changes to fields must update too.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WeatherResponse {
+    #[serde(serialize_with = "as_string")]
+    #[schema(value_type = String, example = "4804")]
+    pub martian_sol_day: i64,
+    pub terrestrial_date: NaiveDate,
+    /// Approximate UTC start and end of the sol. A sol spans more than one Earth day, so it overlaps two dates.
+    #[serde(skip_serializing_if = "skip_missing")]
+    pub sol_start: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "skip_missing")]
+    pub sol_end: Option<DateTime<Utc>>,
+    /// How much longer the sol is than an Earth day, in seconds
+    #[schema(example = 2375.245)]
+    pub sol_drift_seconds: f64,
+
+    #[serde(serialize_with = "as_string_or_na")]
+    #[schema(value_type = String, example = "-74")]
+    pub min_temp: Option<f64>,
+    #[serde(serialize_with = "as_string_or_na")]
+    #[schema(value_type = String, example = "-7")]
+    pub max_temp: Option<f64>,
+    #[schema(example = "c")]
+    pub units: &'static str,
+    pub temperature: Temperature,
+
+    #[serde(serialize_with = "as_string_or_na")]
+    #[schema(value_type = String, example = "750")]
+    pub pressure: Option<f64>,
+    #[serde(serialize_with = "as_string_or_na")]
+    #[schema(value_type = String, example = "Sunny")]
+    pub atmo_opacity: Option<String>,
+    #[serde(serialize_with = "as_string_or_na")]
+    #[schema(value_type = String, example = "Month 4")]
+    pub season: Option<String>,
+
+    /// `N/A` when NASA did not report it for the sol
+    #[serde(serialize_with = "as_string_or_na")]
+    #[schema(value_type = String, example = "05:19:00")]
+    pub sunrise: Option<NaiveTime>,
+    #[serde(serialize_with = "as_string_or_na")]
+    #[schema(value_type = String, example = "17:22:00")]
+    pub sunset: Option<NaiveTime>,
+    /// Time scale of sunrise and sunset, which are Martian times rather than times of an Earth time zone
+    #[serde(skip_serializing_if = "skip_missing")]
+    #[schema(example = "Mars local mean solar time")]
+    pub tz_label: Option<&'static str>,
+
+    /// Set when the closest available sol is returned instead of the requested one
+    #[serde(
+        skip_serializing_if = "skip_missing",
+        serialize_with = "as_string_or_na"
+    )]
+    #[schema(value_type = Option<String>)]
+    pub requested_sol: Option<i64>,
+    #[serde(
+        skip_serializing_if = "skip_missing",
+        serialize_with = "as_string_or_na"
+    )]
+    #[schema(value_type = Option<String>)]
+    pub returned_sol: Option<i64>,
+    #[serde(skip_serializing_if = "skip_missing")]
+    pub sol_delta: Option<i64>,
+}
+
+impl WeatherResponse {
+    pub fn new(data: &SoleData, units: TemperatureUnit) -> Self {
+        let span = sol_span(data.sol.0);
+        WeatherResponse {
+            martian_sol_day: data.sol.0,
+            terrestrial_date: data.terrestrial_date,
+            sol_start: span.map(|(start, _)| start),
+            sol_end: span.map(|(_, end)| end),
+            sol_drift_seconds: SOL_DRIFT_SECS,
+
+            min_temp: data.min_temp.map(|temp| units.convert(temp)),
+            max_temp: data.max_temp.map(|temp| units.convert(temp)),
+            units: units.as_str(),
+            temperature: Temperature::new(data, units),
+
+            pressure: data.pressure,
+            atmo_opacity: data.atmo_opacity.clone(),
+            season: data.season.clone(),
+
+            sunrise: data.sunrise,
+            sunset: data.sunset,
+            tz_label: tz_label(data),
+
+            requested_sol: None,
+            returned_sol: None,
+            sol_delta: None,
+        }
+    }
+
+    /// Notes the requested sol when this response is for the closest available sol instead
+    pub fn with_requested_sol(mut self, requested_sol: i64) -> Self {
+        self.requested_sol = Some(requested_sol);
+        self.returned_sol = Some(self.martian_sol_day);
+        self.sol_delta = Some(self.martian_sol_day - requested_sol);
+        self
+    }
+}
+
+/// Temperatures of a sol as numbers, with the mean of the lowest and highest when both are known
+#[derive(Debug, Serialize, ToSchema)]
+pub struct Temperature {
+    #[schema(example = -74.0)]
+    pub min: Option<f64>,
+    #[schema(example = -7.0)]
+    pub max: Option<f64>,
+    #[schema(example = -40.5)]
+    pub mean: Option<f64>,
+    #[schema(example = "c")]
+    pub unit: &'static str,
+}
+
+impl Temperature {
+    pub fn new(data: &SoleData, units: TemperatureUnit) -> Self {
+        Temperature {
+            min: data.min_temp.map(|temp| units.convert(temp)),
+            max: data.max_temp.map(|temp| units.convert(temp)),
+            mean: data
+                .min_temp
+                .zip(data.max_temp)
+                .map(|(min, max)| units.convert_mean(min, max)),
+            unit: units.as_str(),
+        }
+    }
+}
+
+/// Fields of weather responses that can be selected with the `fields` query parameter
+const WEATHER_FIELDS: &[&str] = &[
+    "terrestrial_date",
+    "sol_start",
+    "sol_end",
+    "sol_drift_seconds",
+    "min_temp",
+    "max_temp",
+    "units",
+    "temperature",
+    "pressure",
+    "atmo_opacity",
+    "season",
+    "sunrise",
+    "sunset",
+    "tz_label",
+    "requested_sol",
+    "returned_sol",
+    "sol_delta",
+];
+
+/// Fields of weather responses to return, parsed from a comma-separated list. The sol is always included.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeatherFields(Vec<String>);
+
+impl FromStr for WeatherFields {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = Vec::new();
+        for field in s
+            .split(',')
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+        {
+            if field != "martian_sol_day" && !WEATHER_FIELDS.contains(&field) {
+                return Err(anyhow!(
+                    "Unknown field '{field}'. Allowed fields are {}.",
+                    WEATHER_FIELDS.join(", ")
+                ));
+            }
+            fields.push(field.to_string());
+        }
+        Ok(WeatherFields(fields))
+    }
+}
+
+impl WeatherFields {
+    /// Keeps only the selected fields and the sol of a weather response
+    pub fn project(
+        &self,
+        response: &WeatherResponse,
+    ) -> serde_json::Result<serde_json::Map<String, serde_json::Value>> {
+        let serde_json::Value::Object(map) = serde_json::to_value(response)? else {
+            return Ok(serde_json::Map::new());
+        };
+        Ok(map
+            .into_iter()
+            .filter(|(key, _)| key == "martian_sol_day" || self.0.contains(key))
+            .collect())
+    }
+}
+
+/// Leaves out missing optional fields, unless requested with `compact=false`
+fn skip_missing<T>(value: &Option<T>) -> bool {
+    value.is_none() && !explicit_nulls()
+}
+
+fn as_string<T: Display, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
+fn as_string_or_na<T: Display, S: Serializer>(
+    value: &Option<T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(value) => serializer.collect_str(value),
+        None if explicit_nulls() => serializer.serialize_none(),
+        None => serializer.serialize_str("N/A"),
+    }
+}
+
+/// Weather data for a single sol with temperatures in Kelvin as numbers, for scientific consumers.
+/// Missing values are `null`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct KelvinWeatherResponse {
+    #[serde(serialize_with = "as_string")]
+    #[schema(value_type = String, example = "4804")]
+    pub martian_sol_day: i64,
+    pub terrestrial_date: NaiveDate,
+
+    pub min_temp: Option<f64>,
+    pub max_temp: Option<f64>,
+    pub units: &'static str,
+
+    pub sunrise: Option<NaiveTime>,
+    pub sunset: Option<NaiveTime>,
+    #[serde(skip_serializing_if = "skip_missing")]
+    #[schema(example = "Mars local mean solar time")]
+    pub tz_label: Option<&'static str>,
+}
+
+impl KelvinWeatherResponse {
+    pub fn new(data: &SoleData) -> Self {
+        let kelvin = TemperatureUnit::Kelvin;
+        KelvinWeatherResponse {
+            martian_sol_day: data.sol.0,
+            terrestrial_date: data.terrestrial_date,
+            min_temp: data.min_temp.map(|temp| kelvin.convert(temp)),
+            max_temp: data.max_temp.map(|temp| kelvin.convert(temp)),
+            units: kelvin.as_str(),
+            sunrise: data.sunrise,
+            sunset: data.sunset,
+            tz_label: tz_label(data),
+        }
+    }
+}
+
+/// Lowest and highest temperatures across the sols within `radius` of a sol
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WeatherWindowResponse {
+    #[serde(serialize_with = "as_string")]
+    #[schema(value_type = String, example = "4804")]
+    pub martian_sol_day: i64,
+    pub radius: i64,
+    /// Number of sols within the window with at least one temperature
+    pub sol_count: usize,
+
+    #[serde(serialize_with = "as_string_or_na")]
+    #[schema(value_type = String, example = "-78")]
+    pub min_temp: Option<f64>,
+    #[serde(serialize_with = "as_string_or_na")]
+    #[schema(value_type = String, example = "-3")]
+    pub max_temp: Option<f64>,
+    pub units: &'static str,
+}
+
+impl WeatherWindowResponse {
+    /// Aggregates temperatures of the sols, skipping missing values
+    pub fn new(sol: i64, radius: i64, soles: &[SoleData], units: TemperatureUnit) -> Self {
+        WeatherWindowResponse {
+            martian_sol_day: sol,
+            radius,
+            sol_count: soles
+                .iter()
+                .filter(|data| data.min_temp.is_some() || data.max_temp.is_some())
+                .count(),
+            min_temp: soles
+                .iter()
+                .filter_map(|data| data.min_temp)
+                .min()
+                .map(|temp| units.convert(temp)),
+            max_temp: soles
+                .iter()
+                .filter_map(|data| data.max_temp)
+                .max()
+                .map(|temp| units.convert(temp)),
+            units: units.as_str(),
+        }
+    }
+}
+
+/// Aggregates across every cached sol of a rover
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WeatherStatsResponse {
+    pub sol_count: usize,
+    #[serde(serialize_with = "as_string")]
+    #[schema(value_type = String, example = "4790")]
+    pub first_sol: i64,
+    #[serde(serialize_with = "as_string")]
+    #[schema(value_type = String, example = "4805")]
+    pub last_sol: i64,
+    pub first_date: NaiveDate,
+    pub last_date: NaiveDate,
+
+    /// Lowest recorded temperature, `null` when no sol has one
+    pub coldest: Option<TemperatureRecord>,
+    /// Highest recorded temperature, `null` when no sol has one
+    pub warmest: Option<TemperatureRecord>,
+    pub units: &'static str,
+}
+
+/// Temperature together with the sol it was recorded on
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+pub struct TemperatureRecord {
+    #[serde(serialize_with = "as_string")]
+    #[schema(value_type = String, example = "4797")]
+    pub martian_sol_day: i64,
+    pub terrestrial_date: NaiveDate,
+    #[serde(serialize_with = "as_string")]
+    #[schema(value_type = String, example = "-81")]
+    pub temp: f64,
+}
+
+impl WeatherStatsResponse {
+    /// Aggregates the sols, skipping missing temperatures. Ties go to the earliest sol. Returns `None` without sols.
+    pub fn new<'a>(
+        soles: impl Iterator<Item = &'a SoleData> + Clone,
+        units: TemperatureUnit,
+    ) -> Option<Self> {
+        let first = soles.clone().min_by_key(|data| data.sol.0)?;
+        let last = soles.clone().max_by_key(|data| data.sol.0)?;
+
+        let record = |data: &SoleData, temp: i64| TemperatureRecord {
+            martian_sol_day: data.sol.0,
+            terrestrial_date: data.terrestrial_date,
+            temp: units.convert(temp),
+        };
+        let coldest = soles
+            .clone()
+            .filter_map(|data| data.min_temp.map(|temp| (temp, data)))
+            .min_by_key(|(temp, data)| (*temp, data.sol.0))
+            .map(|(temp, data)| record(data, temp));
+        let warmest = soles
+            .clone()
+            .filter_map(|data| data.max_temp.map(|temp| (temp, data)))
+            .max_by_key(|(temp, data)| (*temp, -data.sol.0))
+            .map(|(temp, data)| record(data, temp));
+
+        Some(WeatherStatsResponse {
+            sol_count: soles.count(),
+            first_sol: first.sol.0,
+            last_sol: last.sol.0,
+            first_date: first.terrestrial_date,
+            last_date: last.terrestrial_date,
+            coldest,
+            warmest,
+            units: units.as_str(),
+        })
+    }
+}
+
+/// Coldest and warmest sols of a rover, ordered from the most extreme
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WeatherExtremesResponse {
+    /// Sols with the lowest `min_temp`, lowest first
+    pub coldest: Vec<ExtremeSol>,
+    /// Sols with the highest `max_temp`, highest first
+    pub warmest: Vec<ExtremeSol>,
+    pub units: &'static str,
+}
+
+/// Temperatures of a sol in the extremes
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+pub struct ExtremeSol {
+    #[serde(serialize_with = "as_string")]
+    #[schema(value_type = String, example = "4797")]
+    pub martian_sol_day: i64,
+    pub terrestrial_date: NaiveDate,
+    #[serde(serialize_with = "as_string_or_na")]
+    #[schema(value_type = String, example = "-81")]
+    pub min_temp: Option<f64>,
+    #[serde(serialize_with = "as_string_or_na")]
+    #[schema(value_type = String, example = "-9")]
+    pub max_temp: Option<f64>,
+}
+
+impl WeatherExtremesResponse {
+    /// Takes the `n` coldest and warmest sols, skipping sols missing the temperature sorted on.
+    /// Ties go to the earliest sol.
+    pub fn new<'a>(
+        soles: impl Iterator<Item = &'a SoleData> + Clone,
+        n: usize,
+        units: TemperatureUnit,
+    ) -> Self {
+        let entry = |data: &SoleData| ExtremeSol {
+            martian_sol_day: data.sol.0,
+            terrestrial_date: data.terrestrial_date,
+            min_temp: data.min_temp.map(|temp| units.convert(temp)),
+            max_temp: data.max_temp.map(|temp| units.convert(temp)),
+        };
+
+        let mut coldest: Vec<(i64, &SoleData)> = soles
+            .clone()
+            .filter_map(|data| data.min_temp.map(|temp| (temp, data)))
+            .collect();
+        coldest.sort_by_key(|(temp, data)| (*temp, data.sol.0));
+        let mut warmest: Vec<(i64, &SoleData)> = soles
+            .filter_map(|data| data.max_temp.map(|temp| (temp, data)))
+            .collect();
+        warmest.sort_by_key(|(temp, data)| (-*temp, data.sol.0));
+
+        WeatherExtremesResponse {
+            coldest: coldest
+                .iter()
+                .take(n)
+                .map(|(_, data)| entry(data))
+                .collect(),
+            warmest: warmest
+                .iter()
+                .take(n)
+                .map(|(_, data)| entry(data))
+                .collect(),
+            units: units.as_str(),
+        }
+    }
+}
+
+/// Weather of the sols of two dates side by side, with how temperatures changed from `date1` to `date2`
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WeatherCompareResponse {
+    #[schema(example = 4790)]
+    pub sol1: i64,
+    #[schema(example = 4804)]
+    pub sol2: i64,
+    /// Weather of the sol of `date1`, `null` when there is no data for it
+    pub date1: Option<WeatherResponse>,
+    /// Weather of the sol of `date2`, `null` when there is no data for it
+    pub date2: Option<WeatherResponse>,
+    /// `min_temp` of `date2` minus the one of `date1` in `unit`, `null` when either is missing
+    #[schema(example = 2.0)]
+    pub min_temp_delta: Option<f64>,
+    /// `max_temp` of `date2` minus the one of `date1` in `unit`, `null` when either is missing
+    #[schema(example = -1.0)]
+    pub max_temp_delta: Option<f64>,
+    #[schema(example = "c")]
+    pub unit: &'static str,
+}
+
+impl WeatherCompareResponse {
+    pub fn new(
+        (sol1, data1): (i64, Option<&SoleData>),
+        (sol2, data2): (i64, Option<&SoleData>),
+        units: TemperatureUnit,
+    ) -> Self {
+        let delta = |temp: fn(&SoleData) -> Option<i64>| {
+            let from = data1.and_then(temp)?;
+            let to = data2.and_then(temp)?;
+            Some(units.convert_delta(from, to))
+        };
+        WeatherCompareResponse {
+            sol1,
+            sol2,
+            date1: data1.map(|data| WeatherResponse::new(data, units)),
+            date2: data2.map(|data| WeatherResponse::new(data, units)),
+            min_temp_delta: delta(|data| data.min_temp),
+            max_temp_delta: delta(|data| data.max_temp),
+            unit: units.as_str(),
+        }
+    }
+}
+
+/// Sunrise, sunset and the duration of daylight between them for a single sol
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DaylightResponse {
+    #[serde(serialize_with = "as_string")]
+    #[schema(value_type = String, example = "4804")]
+    pub martian_sol_day: i64,
+    pub terrestrial_date: NaiveDate,
+
+    pub sunrise: NaiveTime,
+    pub sunset: NaiveTime,
+    /// Time scale of sunrise and sunset
+    #[schema(example = "Mars local mean solar time")]
+    pub tz_label: &'static str,
+
+    /// Whole hours of daylight
+    #[schema(example = 12)]
+    pub daylight_hours: i64,
+    /// Minutes of daylight in addition to `daylight_hours`
+    #[schema(example = 3)]
+    pub daylight_minutes: i64,
+}
+
+impl DaylightResponse {
+    /// Returns `None` when the sol is missing sunrise or sunset
+    pub fn new(data: &SoleData) -> Option<Self> {
+        let (sunrise, sunset) = (data.sunrise?, data.sunset?);
+        let daylight = daylight(sunrise, sunset);
+        Some(DaylightResponse {
+            martian_sol_day: data.sol.0,
+            terrestrial_date: data.terrestrial_date,
+            sunrise,
+            sunset,
+            tz_label: MARS_TIME_LABEL,
+            daylight_hours: daylight.num_hours(),
+            daylight_minutes: daylight.num_minutes() % 60,
+        })
+    }
+}
+
+/// Duration from sunrise to sunset, wrapping past midnight when sunset is earlier than sunrise
+fn daylight(sunrise: NaiveTime, sunset: NaiveTime) -> chrono::Duration {
+    let daylight = sunset - sunrise;
+    if daylight < chrono::Duration::zero() {
+        daylight + chrono::Duration::hours(24)
+    } else {
+        daylight
+    }
+}
+
+/// Entry of range responses. Sols without data are kept to mark the gap.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum WeatherRangeEntry {
+    Data(Box<WeatherResponse>),
+    NoData {
+        martian_sol_day: String,
+        message: &'static str,
+    },
+}
+
+/// Entry of batch responses for a single requested date, in the order of the request
+#[derive(Debug, Serialize, ToSchema)]
+pub struct WeatherBatchEntry {
+    /// Date as sent in the request
+    pub date: String,
+    #[serde(flatten)]
+    pub result: WeatherBatchResult,
+}
+
+/// Weather data of a batch entry, or why the date could not be looked up
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum WeatherBatchResult {
+    Weather(WeatherRangeEntry),
+    Error(ErrorBody),
+}
+
+/// Body of error responses
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorBody {
+    /// Machine readable error code, e.g. `INVALID_DATE_FORMAT`
+    pub error: String,
+    /// Human readable description of the error
+    pub message: String,
+    /// Id of the request, as sent in `X-Request-Id` or generated, for correlating with server logs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+/// Body of `404 Not Found` responses for a sol outside the range of cached sols, so clients can correct the request
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SolOutOfRangeBody {
+    #[serde(flatten)]
+    pub error: ErrorBody,
+    /// Earliest and latest cached sol of the rover
+    #[schema(example = 4790)]
+    pub first_sol: i64,
+    #[schema(example = 4805)]
+    pub last_sol: i64,
+}
+
+/// Serializes a single weather response as XML with a `weather` root element
+pub fn weather_xml(response: &WeatherResponse) -> anyhow::Result<String> {
+    Ok(quick_xml::se::to_string_with_root("weather", response)?)
+}
+
+/// Serializes range entries as XML with one `sol` element per entry inside a `soles` root element
+pub fn weather_range_xml(entries: &[WeatherRangeEntry]) -> anyhow::Result<String> {
+    #[derive(Serialize)]
+    struct Soles<'a> {
+        sol: &'a [WeatherRangeEntry],
+    }
+
+    Ok(quick_xml::se::to_string_with_root(
+        "soles",
+        &Soles { sol: entries },
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weather_json() {
+        let data = SoleData {
+            min_temp: None,
+            pressure: None,
+            ..SoleData::fixture(4804)
+        };
+        let response =
+            WeatherResponse::new(&data, TemperatureUnit::Fahrenheit).with_requested_sol(4806);
+
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            serde_json::json!({
+                "martian_sol_day": "4804",
+                "terrestrial_date": "2026-02-10",
+                "sol_start": "2026-02-09T06:15:21Z",
+                "sol_end": "2026-02-10T06:54:56Z",
+                "sol_drift_seconds": 2375.245,
+                "min_temp": "N/A",
+                "max_temp": "19.4",
+                "units": "f",
+                "temperature": {"min": null, "max": 19.4, "mean": null, "unit": "f"},
+                "pressure": "N/A",
+                "atmo_opacity": "Sunny",
+                "season": "Month 4",
+                "sunrise": "05:19:00",
+                "sunset": "17:22:00",
+                "tz_label": "Mars local mean solar time",
+                "requested_sol": "4806",
+                "returned_sol": "4804",
+                "sol_delta": -2
+            })
+        );
+
+        // InSight sols have no sunrise or sunset to label
+        let insight = SoleData {
+            sunrise: None,
+            sunset: None,
+            ..SoleData::fixture(4804)
+        };
+        assert_eq!(
+            WeatherResponse::new(&insight, TemperatureUnit::Celsius).tz_label,
+            None
+        );
+    }
+
+    #[test]
+    fn test_weather_fields() {
+        let fields = "min_temp, max_temp".parse::<WeatherFields>().unwrap();
+        let response = WeatherResponse::new(&SoleData::fixture(4804), TemperatureUnit::Celsius);
+
+        assert_eq!(
+            serde_json::Value::Object(fields.project(&response).unwrap()),
+            serde_json::json!({
+                "martian_sol_day": "4804",
+                "min_temp": "-74",
+                "max_temp": "-7"
+            })
+        );
+
+        let only_sol = "".parse::<WeatherFields>().unwrap();
+        assert_eq!(only_sol.project(&response).unwrap().len(), 1);
+
+        assert!("min_temp,humidity".parse::<WeatherFields>().is_err());
+    }
+
+    #[test]
+    fn test_kelvin_weather_json() {
+        let data = SoleData {
+            max_temp: None,
+            ..SoleData::fixture(4804)
+        };
+
+        assert_eq!(
+            serde_json::to_value(KelvinWeatherResponse::new(&data)).unwrap(),
+            serde_json::json!({
+                "martian_sol_day": "4804",
+                "terrestrial_date": "2026-02-10",
+                "min_temp": 199.2,
+                "max_temp": null,
+                "units": "k",
+                "sunrise": "05:19:00",
+                "sunset": "17:22:00",
+                "tz_label": "Mars local mean solar time"
+            })
+        );
+    }
+
+    #[test]
+    fn test_weather_window() {
+        let soles = [
+            SoleData {
+                min_temp: Some(-80),
+                max_temp: None,
+                ..SoleData::fixture(4803)
+            },
+            SoleData {
+                min_temp: None,
+                max_temp: None,
+                ..SoleData::fixture(4804)
+            },
+            SoleData {
+                min_temp: Some(-70),
+                max_temp: Some(-2),
+                ..SoleData::fixture(4805)
+            },
+        ];
+
+        let window = WeatherWindowResponse::new(4804, 1, &soles, TemperatureUnit::Celsius);
+        assert_eq!(window.sol_count, 2);
+        assert_eq!(window.min_temp, Some(-80.0));
+        assert_eq!(window.max_temp, Some(-2.0));
+
+        let empty = WeatherWindowResponse::new(4804, 1, &[], TemperatureUnit::Celsius);
+        assert_eq!(empty.sol_count, 0);
+        assert_eq!(
+            serde_json::to_value(&empty).unwrap()["min_temp"],
+            serde_json::json!("N/A")
+        );
+    }
+
+    #[test]
+    fn test_weather_stats() {
+        let soles = [
+            SoleData {
+                min_temp: Some(-80),
+                max_temp: Some(-5),
+                ..SoleData::fixture(4805)
+            },
+            SoleData {
+                min_temp: None,
+                max_temp: Some(-2),
+                ..SoleData::fixture(4803)
+            },
+            SoleData {
+                min_temp: Some(-80),
+                max_temp: Some(-2),
+                ..SoleData::fixture(4804)
+            },
+        ];
+
+        let stats = WeatherStatsResponse::new(soles.iter(), TemperatureUnit::Celsius).unwrap();
+        assert_eq!(stats.sol_count, 3);
+        assert_eq!((stats.first_sol, stats.last_sol), (4803, 4805));
+        assert_eq!(
+            stats.coldest,
+            Some(TemperatureRecord {
+                martian_sol_day: 4804,
+                terrestrial_date: soles[2].terrestrial_date,
+                temp: -80.0
+            })
+        );
+        assert_eq!(
+            stats.warmest.map(|record| record.martian_sol_day),
+            Some(4803)
+        );
+
+        let no_temps = [SoleData {
+            min_temp: None,
+            max_temp: None,
+            ..SoleData::fixture(4804)
+        }];
+        let stats = WeatherStatsResponse::new(no_temps.iter(), TemperatureUnit::Celsius).unwrap();
+        assert_eq!(stats.coldest, None);
+        assert_eq!(
+            serde_json::to_value(&stats).unwrap()["warmest"],
+            serde_json::Value::Null
+        );
+
+        assert!(WeatherStatsResponse::new([].iter(), TemperatureUnit::Celsius).is_none());
+    }
+
+    #[test]
+    fn test_weather_extremes() {
+        let soles = [
+            SoleData {
+                min_temp: Some(-80),
+                max_temp: None,
+                ..SoleData::fixture(4805)
+            },
+            SoleData {
+                min_temp: None,
+                max_temp: Some(-2),
+                ..SoleData::fixture(4803)
+            },
+            SoleData {
+                min_temp: Some(-80),
+                max_temp: Some(-2),
+                ..SoleData::fixture(4804)
+            },
+            SoleData::fixture(4802),
+        ];
+        let sols = |entries: &[ExtremeSol]| {
+            entries
+                .iter()
+                .map(|entry| entry.martian_sol_day)
+                .collect::<Vec<_>>()
+        };
+
+        let extremes = WeatherExtremesResponse::new(soles.iter(), 2, TemperatureUnit::Celsius);
+        assert_eq!(sols(&extremes.coldest), [4804, 4805]);
+        assert_eq!(sols(&extremes.warmest), [4803, 4804]);
+        assert_eq!(
+            extremes.coldest[1],
+            ExtremeSol {
+                martian_sol_day: 4805,
+                terrestrial_date: soles[0].terrestrial_date,
+                min_temp: Some(-80.0),
+                max_temp: None,
+            }
+        );
+
+        let extremes = WeatherExtremesResponse::new(soles.iter(), 10, TemperatureUnit::Celsius);
+        assert_eq!(sols(&extremes.coldest), [4804, 4805, 4802]);
+        assert_eq!(sols(&extremes.warmest), [4803, 4804, 4802]);
+    }
+
+    #[test]
+    fn test_daylight() {
+        let response = DaylightResponse::new(&SoleData::fixture(4804)).unwrap();
+        assert_eq!(response.daylight_hours, 12);
+        assert_eq!(response.daylight_minutes, 3);
+
+        let wrapped = DaylightResponse::new(&SoleData {
+            sunrise: NaiveTime::from_hms_opt(22, 30, 0),
+            sunset: NaiveTime::from_hms_opt(10, 15, 0),
+            ..SoleData::fixture(4804)
+        })
+        .unwrap();
+        assert_eq!(wrapped.daylight_hours, 11);
+        assert_eq!(wrapped.daylight_minutes, 45);
+
+        let without_sunset = SoleData {
+            sunset: None,
+            ..SoleData::fixture(4804)
+        };
+        assert!(DaylightResponse::new(&without_sunset).is_none());
+    }
+
+    #[test]
+    fn test_weather_batch_json() {
+        let entries = [
+            WeatherBatchEntry {
+                date: "2026-02-10".to_string(),
+                result: WeatherBatchResult::Weather(WeatherRangeEntry::NoData {
+                    martian_sol_day: "4804".to_string(),
+                    message: "No data found for sol",
+                }),
+            },
+            WeatherBatchEntry {
+                date: "yesterday".to_string(),
+                result: WeatherBatchResult::Error(ErrorBody {
+                    error: "INVALID_DATE_FORMAT".to_string(),
+                    message: "Invalid format for date.".to_string(),
+                    request_id: None,
+                }),
+            },
+        ];
+
+        assert_eq!(
+            serde_json::to_value(entries).unwrap(),
+            serde_json::json!([
+                {
+                    "date": "2026-02-10",
+                    "martian_sol_day": "4804",
+                    "message": "No data found for sol"
+                },
+                {
+                    "date": "yesterday",
+                    "error": "INVALID_DATE_FORMAT",
+                    "message": "Invalid format for date."
+                }
+            ])
+        );
+    }
+
+    #[test]
+    fn test_weather_xml() {
+        let data = SoleData {
+            max_temp: None,
+            ..SoleData::fixture(4804)
+        };
+        let response = WeatherResponse::new(&data, TemperatureUnit::Celsius);
+
+        assert_eq!(
+            weather_xml(&response).unwrap(),
+            "<weather>\
+             <martian_sol_day>4804</martian_sol_day>\
+             <terrestrial_date>2026-02-10</terrestrial_date>\
+             <sol_start>2026-02-09T06:15:21Z</sol_start>\
+             <sol_end>2026-02-10T06:54:56Z</sol_end>\
+             <sol_drift_seconds>2375.245</sol_drift_seconds>\
+             <min_temp>-74</min_temp>\
+             <max_temp>N/A</max_temp>\
+             <units>c</units>\
+             <temperature><min>-74</min><max/><mean/><unit>c</unit></temperature>\
+             <pressure>750</pressure>\
+             <atmo_opacity>Sunny</atmo_opacity>\
+             <season>Month 4</season>\
+             <sunrise>05:19:00</sunrise>\
+             <sunset>17:22:00</sunset>\
+             <tz_label>Mars local mean solar time</tz_label>\
+             </weather>"
+        );
+    }
+
+    #[test]
+    fn test_weather_range_xml() {
+        let entries = vec![
+            WeatherRangeEntry::Data(Box::new(WeatherResponse::new(
+                &SoleData::fixture(4804),
+                TemperatureUnit::Celsius,
+            ))),
+            WeatherRangeEntry::NoData {
+                martian_sol_day: "4805".to_string(),
+                message: "No data found for sol",
+            },
+        ];
+
+        let xml = weather_range_xml(&entries).unwrap();
+
+        assert!(xml.starts_with("<soles><sol><martian_sol_day>4804</martian_sol_day>"));
+        assert!(xml.ends_with(
+            "<sol><martian_sol_day>4805</martian_sol_day><message>No data found for sol</message></sol></soles>"
+        ));
+    }
+}
@@ -0,0 +1,286 @@
+use std::path::Path;
+
+use rusqlite::{Connection, Row, params};
+
+use crate::{Sole, SoleData, SolesData};
+
+// Columns added after the table was first created, added to existing databases on open
+const ADDED_COLUMNS: &[(&str, &str)] = &[
+    ("pressure", "REAL"),
+    ("atmo_opacity", "TEXT"),
+    ("season", "TEXT"),
+];
+
+const CREATE_SOLES_TABLE: &str = "CREATE TABLE IF NOT EXISTS soles (
+    rover TEXT NOT NULL,
+    sol INTEGER NOT NULL,
+    id TEXT NOT NULL,
+    terrestrial_date TEXT NOT NULL,
+    min_temp INTEGER,
+    max_temp INTEGER,
+    sunrise TEXT,
+    sunset TEXT,
+    pressure REAL,
+    atmo_opacity TEXT,
+    season TEXT,
+    PRIMARY KEY (rover, sol)
+)";
+
+fn open(path: &Path) -> anyhow::Result<Connection> {
+    let mut connection = Connection::open(path)?;
+    connection.execute(CREATE_SOLES_TABLE, ())?;
+
+    // Names of existing columns and whether they are NOT NULL
+    let existing_columns = connection
+        .prepare("SELECT name, \"notnull\" FROM pragma_table_info('soles')")?
+        .query_map((), |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?))
+        })?
+        .collect::<Result<Vec<(String, bool)>, _>>()?;
+    let has_column = |column: &str| existing_columns.iter().any(|(name, _)| name == column);
+    for (column, column_type) in ADDED_COLUMNS {
+        if !has_column(column) {
+            connection.execute(
+                &format!("ALTER TABLE soles ADD COLUMN {column} {column_type}"),
+                (),
+            )?;
+        }
+    }
+
+    // Tables created before multiple rovers were supported are keyed by sol alone and only hold msl soles.
+    // Tables created before feeds without sunrise and sunset were supported require both.
+    let has_rover = has_column("rover");
+    let requires_sunrise = existing_columns
+        .iter()
+        .any(|(name, not_null)| name == "sunrise" && *not_null);
+    if !has_rover || requires_sunrise {
+        let rover = if has_rover { "rover" } else { "'msl'" };
+        let transaction = connection.transaction()?;
+        transaction.execute("ALTER TABLE soles RENAME TO soles_before_migration", ())?;
+        transaction.execute(CREATE_SOLES_TABLE, ())?;
+        transaction.execute(
+            &format!(
+                "INSERT INTO soles
+                    (rover, sol, id, terrestrial_date, min_temp, max_temp, sunrise, sunset, pressure, atmo_opacity, season)
+                 SELECT {rover}, sol, id, terrestrial_date, min_temp, max_temp, sunrise, sunset, pressure, atmo_opacity, season
+                 FROM soles_before_migration"
+            ),
+            (),
+        )?;
+        transaction.execute("DROP TABLE soles_before_migration", ())?;
+        transaction.commit()?;
+    }
+
+    Ok(connection)
+}
+
+/// Stores soles in the SQLite database at `path`, replacing existing rows with the same rover and sol
+pub fn save_soles(path: &Path, soles: &SolesData) -> anyhow::Result<()> {
+    let mut connection = open(path)?;
+    let transaction = connection.transaction()?;
+    {
+        let mut statement = transaction.prepare(
+            "INSERT OR REPLACE INTO soles
+                (rover, sol, id, terrestrial_date, min_temp, max_temp, sunrise, sunset, pressure, atmo_opacity, season)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+        )?;
+        for ((rover, _), data) in soles {
+            statement.execute(params![
+                rover,
+                data.sol.0,
+                data.id,
+                data.terrestrial_date,
+                data.min_temp,
+                data.max_temp,
+                data.sunrise,
+                data.sunset,
+                data.pressure,
+                data.atmo_opacity,
+                data.season,
+            ])?;
+        }
+    }
+    transaction.commit()?;
+    Ok(())
+}
+
+/// Loads all soles stored in the SQLite database at `path`
+pub fn load_soles(path: &Path) -> anyhow::Result<SolesData> {
+    let connection = open(path)?;
+    let mut statement = connection.prepare(
+        "SELECT rover, sol, id, terrestrial_date, min_temp, max_temp, sunrise, sunset, pressure, atmo_opacity, season
+         FROM soles",
+    )?;
+
+    let soles = statement
+        .query_map((), |row| {
+            let rover: String = row.get("rover")?;
+            let data = sole_data_from_row(row)?;
+            Ok(((rover, data.sol.clone()), data))
+        })?
+        .collect::<Result<SolesData, _>>()?;
+    Ok(soles)
+}
+
+fn sole_data_from_row(row: &Row) -> rusqlite::Result<SoleData> {
+    Ok(SoleData {
+        sol: Sole(row.get("sol")?),
+        id: row.get("id")?,
+        terrestrial_date: row.get("terrestrial_date")?,
+        min_temp: row.get("min_temp")?,
+        max_temp: row.get("max_temp")?,
+        sunrise: row.get("sunrise")?,
+        sunset: row.get("sunset")?,
+        pressure: row.get("pressure")?,
+        atmo_opacity: row.get("atmo_opacity")?,
+        season: row.get("season")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::NaiveTime;
+
+    use super::*;
+
+    #[test]
+    fn test_save_and_load_soles() {
+        let path = std::env::temp_dir().join(format!("linx-test-{}.db", std::process::id()));
+        let data = SoleData {
+            max_temp: None,
+            atmo_opacity: None,
+            ..SoleData::fixture(4804)
+        };
+        let without_sunrise = SoleData {
+            sunrise: None,
+            sunset: None,
+            ..SoleData::fixture(4805)
+        };
+
+        let key = ("msl".to_string(), Sole(4804));
+        save_soles(&path, &HashMap::from([(key.clone(), data.clone())])).unwrap();
+        // Saving again replaces the row instead of failing on the duplicate sol
+        save_soles(&path, &HashMap::from([(key.clone(), data.clone())])).unwrap();
+        // Same sol of another rover is stored separately
+        save_soles(
+            &path,
+            &HashMap::from([(("insight".to_string(), Sole(4804)), data.clone())]),
+        )
+        .unwrap();
+        save_soles(
+            &path,
+            &HashMap::from([(("msl".to_string(), Sole(4805)), without_sunrise)]),
+        )
+        .unwrap();
+        let soles = load_soles(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(soles.len(), 3);
+        let loaded = &soles[&key];
+        assert_eq!(loaded.id, data.id);
+        assert_eq!(loaded.terrestrial_date, data.terrestrial_date);
+        assert_eq!(loaded.min_temp, data.min_temp);
+        assert_eq!(loaded.max_temp, None);
+        assert_eq!(loaded.pressure, data.pressure);
+        assert_eq!(loaded.atmo_opacity, None);
+        assert_eq!(loaded.season, data.season);
+        assert_eq!(loaded.sunrise, data.sunrise);
+        assert_eq!(loaded.sunset, data.sunset);
+        assert_eq!(soles[&("msl".to_string(), Sole(4805))].sunrise, None);
+    }
+
+    #[test]
+    fn test_migrate_table_without_rover() {
+        let path =
+            std::env::temp_dir().join(format!("linx-test-migrate-{}.db", std::process::id()));
+        {
+            let connection = Connection::open(&path).unwrap();
+            connection
+                .execute_batch(
+                    "CREATE TABLE soles (
+                        sol INTEGER PRIMARY KEY,
+                        id TEXT NOT NULL,
+                        terrestrial_date TEXT NOT NULL,
+                        min_temp INTEGER,
+                        max_temp INTEGER,
+                        sunrise TEXT NOT NULL,
+                        sunset TEXT NOT NULL
+                    );
+                    INSERT INTO soles VALUES (4804, '4804', '2026-02-10', -74, -7, '05:19:00', '17:22:00');",
+                )
+                .unwrap();
+        }
+
+        let soles = load_soles(&path).unwrap();
+
+        assert_eq!(soles.len(), 1);
+        let loaded = &soles[&("msl".to_string(), Sole(4804))];
+        assert_eq!(loaded.min_temp, Some(-74));
+        assert_eq!(loaded.pressure, None);
+
+        // Sunrise and sunset are no longer required after migrating
+        let without_sunrise = SoleData {
+            sunrise: None,
+            sunset: None,
+            ..SoleData::fixture(4805)
+        };
+        save_soles(
+            &path,
+            &HashMap::from([(("msl".to_string(), Sole(4805)), without_sunrise)]),
+        )
+        .unwrap();
+        assert_eq!(load_soles(&path).unwrap().len(), 2);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_migrate_table_requiring_sunrise() {
+        let path = std::env::temp_dir().join(format!(
+            "linx-test-migrate-sunrise-{}.db",
+            std::process::id()
+        ));
+        {
+            let connection = Connection::open(&path).unwrap();
+            connection
+                .execute_batch(
+                    "CREATE TABLE soles (
+                        rover TEXT NOT NULL,
+                        sol INTEGER NOT NULL,
+                        id TEXT NOT NULL,
+                        terrestrial_date TEXT NOT NULL,
+                        min_temp INTEGER,
+                        max_temp INTEGER,
+                        sunrise TEXT NOT NULL,
+                        sunset TEXT NOT NULL,
+                        pressure REAL,
+                        atmo_opacity TEXT,
+                        season TEXT,
+                        PRIMARY KEY (rover, sol)
+                    );
+                    INSERT INTO soles VALUES ('m2020', 1700, '1700', '2026-02-10', -74, -7, '05:19:00', '17:22:00', 750, 'Sunny', 'Month 4');",
+                )
+                .unwrap();
+        }
+
+        let without_sunrise = SoleData {
+            sunrise: None,
+            sunset: None,
+            ..SoleData::fixture(4805)
+        };
+        save_soles(
+            &path,
+            &HashMap::from([(("msl".to_string(), Sole(4805)), without_sunrise)]),
+        )
+        .unwrap();
+        let soles = load_soles(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(soles.len(), 2);
+        assert_eq!(
+            soles[&("m2020".to_string(), Sole(1700))].sunrise,
+            NaiveTime::from_hms_opt(5, 19, 0)
+        );
+    }
+}
@@ -0,0 +1,100 @@
+use axum::{
+    extract::{FromRequestParts, Query, rejection::QueryRejection},
+    http::{StatusCode, Uri, request::Parts},
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, de::DeserializeOwned};
+use utoipa::IntoParams;
+
+use crate::error_response;
+
+/// Parameters accepted by every route, on top of the ones of the handler
+const GLOBAL_PARAMS: [&str; 3] = ["strict", "pretty", "compact"];
+
+#[derive(Debug, Deserialize)]
+struct StrictParam {
+    strict: Option<bool>,
+}
+
+/// Query extractor that parses like [`Query`], except that requests with `strict=true` are rejected with
+/// `422 Unprocessable Entity` when they contain parameters the handler does not know, e.g. `dat` instead of `date`.
+/// Known parameters are taken from the handler's OpenAPI params.
+pub struct StrictQuery<T>(pub T);
+
+pub enum StrictQueryRejection {
+    Query(QueryRejection),
+    UnknownParams(Vec<String>),
+}
+
+impl IntoResponse for StrictQueryRejection {
+    fn into_response(self) -> Response {
+        match self {
+            StrictQueryRejection::Query(rejection) => rejection.into_response(),
+            StrictQueryRejection::UnknownParams(unknown) => error_response(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "UNKNOWN_PARAMETERS",
+                format!("Unknown query parameters: {}.", unknown.join(", ")),
+            ),
+        }
+    }
+}
+
+impl<T, S> FromRequestParts<S> for StrictQuery<T>
+where
+    T: DeserializeOwned + IntoParams,
+    S: Send + Sync,
+{
+    type Rejection = StrictQueryRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(params) = Query::<T>::from_request_parts(parts, state)
+            .await
+            .map_err(StrictQueryRejection::Query)?;
+
+        let strict = Query::<StrictParam>::try_from_uri(&parts.uri)
+            .is_ok_and(|Query(param)| param.strict == Some(true));
+        if strict {
+            let unknown = unknown_params::<T>(&parts.uri);
+            if !unknown.is_empty() {
+                return Err(StrictQueryRejection::UnknownParams(unknown));
+            }
+        }
+        Ok(StrictQuery(params))
+    }
+}
+
+/// Returns keys of the query string that are neither params of `T` nor global ones, in order of appearance
+fn unknown_params<T: IntoParams>(uri: &Uri) -> Vec<String> {
+    let known: Vec<String> = T::into_params(|| None)
+        .into_iter()
+        .map(|param| param.name)
+        .collect();
+    let mut unknown: Vec<String> = Vec::new();
+    let pairs = Query::<Vec<(String, String)>>::try_from_uri(uri)
+        .map(|Query(pairs)| pairs)
+        .unwrap_or_default();
+    for (key, _) in pairs {
+        if !known.contains(&key)
+            && !GLOBAL_PARAMS.contains(&key.as_str())
+            && !unknown.contains(&key)
+        {
+            unknown.push(key);
+        }
+    }
+    unknown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::KelvinQuery;
+
+    #[test]
+    fn test_unknown_params() {
+        let uri = Uri::from_static("/weather/kelvin?date=2026-02-09&pretty=true&strict=true");
+        assert!(unknown_params::<KelvinQuery>(&uri).is_empty());
+
+        let uri = Uri::from_static("/weather/kelvin?dat=2026-02-09&units=k&dat=2026-02-10");
+        assert_eq!(unknown_params::<KelvinQuery>(&uri), vec!["dat", "units"]);
+    }
+}
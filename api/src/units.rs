@@ -0,0 +1,127 @@
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+/// Decimal places of converted temperatures. Celsius values from NASA are whole degrees, so they are unaffected.
+pub const TEMPERATURE_DECIMALS: i32 = 1;
+
+/// Temperature scale used in responses. NASA reports temperatures in Celsius.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+    Kelvin,
+}
+
+impl TemperatureUnit {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TemperatureUnit::Celsius => "c",
+            TemperatureUnit::Fahrenheit => "f",
+            TemperatureUnit::Kelvin => "k",
+        }
+    }
+
+    /// Converts a temperature in Celsius to this unit, rounded to `TEMPERATURE_DECIMALS` decimal places.
+    /// All response formats go through this so they agree on converted values.
+    pub fn convert(&self, celsius: i64) -> f64 {
+        round(self.convert_exact(celsius as f64))
+    }
+
+    /// Converts the mean of two temperatures in Celsius to this unit, rounded like [`TemperatureUnit::convert`]
+    pub fn convert_mean(&self, celsius: i64, other_celsius: i64) -> f64 {
+        round(self.convert_exact((celsius + other_celsius) as f64 / 2.0))
+    }
+
+    /// Converts both temperatures in Celsius to this unit and returns how much `to_celsius` is above `from_celsius`,
+    /// rounded like [`TemperatureUnit::convert`]. Offsets of the scales cancel out, so only the scale factor applies.
+    pub fn convert_delta(&self, from_celsius: i64, to_celsius: i64) -> f64 {
+        round(self.convert_exact(to_celsius as f64) - self.convert_exact(from_celsius as f64))
+    }
+
+    fn convert_exact(&self, celsius: f64) -> f64 {
+        match self {
+            TemperatureUnit::Celsius => celsius,
+            TemperatureUnit::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+            TemperatureUnit::Kelvin => celsius + 273.15,
+        }
+    }
+}
+
+/// Rounds to `TEMPERATURE_DECIMALS` decimal places
+fn round(value: f64) -> f64 {
+    let scale = 10_f64.powi(TEMPERATURE_DECIMALS);
+    // Rounds to one more decimal place first so ties such as 199.15 K are not skewed by floating point error
+    let finer = (value * scale * 10.0).round();
+    (finer / 10.0).round() / scale
+}
+
+impl FromStr for TemperatureUnit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "c" => Ok(TemperatureUnit::Celsius),
+            "f" => Ok(TemperatureUnit::Fahrenheit),
+            "k" => Ok(TemperatureUnit::Kelvin),
+            _ => Err(anyhow!("Invalid unit. Allowed units are c, f and k.")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert() {
+        assert_eq!(TemperatureUnit::Celsius.convert(-70), -70.0);
+        assert_eq!(TemperatureUnit::Fahrenheit.convert(100), 212.0);
+        assert_eq!(TemperatureUnit::Kelvin.convert(-74), 199.2);
+        assert_eq!(TemperatureUnit::Kelvin.convert(0), 273.2);
+        assert_eq!(TemperatureUnit::Kelvin.convert(-273), 0.2);
+    }
+
+    #[test]
+    fn test_convert_negative_celsius_to_fahrenheit() {
+        assert_eq!(TemperatureUnit::Fahrenheit.convert(-40), -40.0);
+        assert_eq!(TemperatureUnit::Fahrenheit.convert(-7), 19.4);
+        assert_eq!(TemperatureUnit::Fahrenheit.convert(-18), -0.4);
+        assert_eq!(TemperatureUnit::Fahrenheit.convert(-74), -101.2);
+        assert_eq!(TemperatureUnit::Fahrenheit.convert(-127), -196.6);
+    }
+
+    #[test]
+    fn test_convert_mean() {
+        assert_eq!(TemperatureUnit::Celsius.convert_mean(-74, -7), -40.5);
+        assert_eq!(TemperatureUnit::Fahrenheit.convert_mean(-74, -7), -40.9);
+        assert_eq!(TemperatureUnit::Kelvin.convert_mean(-74, -7), 232.7);
+    }
+
+    #[test]
+    fn test_convert_delta() {
+        assert_eq!(TemperatureUnit::Celsius.convert_delta(-74, -7), 67.0);
+        assert_eq!(TemperatureUnit::Fahrenheit.convert_delta(-74, -7), 120.6);
+        assert_eq!(TemperatureUnit::Fahrenheit.convert_delta(-7, -74), -120.6);
+        assert_eq!(TemperatureUnit::Kelvin.convert_delta(-74, -7), 67.0);
+    }
+
+    #[test]
+    fn test_parse_unit() {
+        assert_eq!(
+            "c".parse::<TemperatureUnit>().unwrap(),
+            TemperatureUnit::Celsius
+        );
+        assert_eq!(
+            "F".parse::<TemperatureUnit>().unwrap(),
+            TemperatureUnit::Fahrenheit
+        );
+        assert_eq!(
+            "k".parse::<TemperatureUnit>().unwrap(),
+            TemperatureUnit::Kelvin
+        );
+        assert!("celsius".parse::<TemperatureUnit>().is_err());
+        assert!("".parse::<TemperatureUnit>().is_err());
+    }
+}
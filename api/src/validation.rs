@@ -0,0 +1,90 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+
+use crate::conversion::calculate_no_of_martian_sol_elapsed;
+
+/// Reasons a requested date can not be served
+#[derive(Debug, PartialEq, Eq)]
+pub enum DateError {
+    InvalidFormat(String),
+    InFuture,
+    BeforeLanding,
+}
+
+impl DateError {
+    /// Error code used in error responses
+    pub fn code(&self) -> &'static str {
+        match self {
+            DateError::InvalidFormat(_) => "INVALID_DATE_FORMAT",
+            DateError::InFuture => "DATE_IN_FUTURE",
+            DateError::BeforeLanding => "DATE_BEFORE_LANDING",
+        }
+    }
+}
+
+impl fmt::Display for DateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DateError::InvalidFormat(message) => write!(f, "{message}"),
+            DateError::InFuture => write!(f, "Date is in the future. No weather data exists yet."),
+            DateError::BeforeLanding => write!(
+                f,
+                "Date is before Curiosity landed on 2012-08-06 05:17:00 UTC. No weather data exists."
+            ),
+        }
+    }
+}
+
+/// Checks that weather data can exist for the date, i.e. it is not after today and not before Curiosity's landing
+pub fn validate_date(
+    datetime: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Result<DateTime<Utc>, DateError> {
+    if datetime.date_naive() > now.date_naive() {
+        return Err(DateError::InFuture);
+    }
+    match calculate_no_of_martian_sol_elapsed(datetime) {
+        Ok(sol) if sol >= 1 => Ok(datetime),
+        _ => Err(DateError::BeforeLanding),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339)
+            .expect("Failed to parse date")
+            .to_utc()
+    }
+
+    #[test]
+    fn test_validate_date_landing_boundary() {
+        let now = date("2026-02-10T12:00:00Z");
+
+        assert_eq!(
+            validate_date(date("2012-08-05T23:59:59Z"), now),
+            Err(DateError::BeforeLanding)
+        );
+        // Landing itself is sol 0
+        assert_eq!(
+            validate_date(date("2012-08-06T05:17:00Z"), now),
+            Err(DateError::BeforeLanding)
+        );
+        assert!(validate_date(date("2012-08-06T05:17:01Z"), now).is_ok());
+        assert!(validate_date(date("2012-08-07T00:00:00Z"), now).is_ok());
+    }
+
+    #[test]
+    fn test_validate_date_in_future() {
+        let now = date("2026-02-10T12:00:00Z");
+
+        assert!(validate_date(date("2026-02-10T23:59:59Z"), now).is_ok());
+        assert_eq!(
+            validate_date(date("2026-02-11T00:00:00Z"), now),
+            Err(DateError::InFuture)
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use std::{future::Future, time::Duration};
+
+use tokio_util::sync::CancellationToken;
+
+/// Most restarts of a background task before the watchdog gives up on it
+pub const MAX_RESTARTS: u64 = 5;
+
+// Delay before restarting a task, so a task failing right away does not restart in a tight loop
+pub const RESTART_DELAY: Duration = Duration::from_secs(10);
+
+/// Runs the task spawned by `spawn` until shutdown, respawning it when it panics or exits before shutdown.
+/// `on_restart` is called on every restart, e.g. to record it in metrics. Restarts stop after `max_restarts`,
+/// leaving the task stopped.
+pub async fn supervise<F, Fut>(
+    name: &str,
+    spawn: F,
+    max_restarts: u64,
+    restart_delay: Duration,
+    on_restart: impl Fn(),
+    shutdown: CancellationToken,
+) where
+    F: Fn() -> Fut,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    let mut restarts = 0;
+    loop {
+        let result = tokio::spawn(spawn()).await;
+        if shutdown.is_cancelled() {
+            break;
+        }
+        match result {
+            Ok(()) => tracing::error!("The {name} task exited unexpectedly"),
+            Err(err) => tracing::error!("The {name} task failed. Err: {err}"),
+        }
+        if restarts >= max_restarts {
+            tracing::error!("Not restarting the {name} task after {restarts} restarts");
+            break;
+        }
+
+        tokio::select! {
+            _ = shutdown.cancelled() => break,
+            _ = tokio::time::sleep(restart_delay) => {}
+        }
+        restarts += 1;
+        on_restart();
+        tracing::warn!("Restarting the {name} task ({restarts}/{max_restarts})");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    };
+
+    use super::*;
+    use crate::metrics::Metrics;
+
+    #[tokio::test]
+    async fn test_supervise_restarts_up_to_max() {
+        let metrics = Metrics::default();
+        let runs = Arc::new(AtomicU64::new(0));
+        let spawn = || {
+            let runs = runs.clone();
+            async move {
+                if runs.fetch_add(1, Ordering::Relaxed).is_multiple_of(2) {
+                    panic!("updater panicked");
+                }
+            }
+        };
+
+        supervise(
+            "test",
+            spawn,
+            3,
+            Duration::ZERO,
+            || metrics.record_updater_restart(),
+            CancellationToken::new(),
+        )
+        .await;
+
+        assert_eq!(runs.load(Ordering::Relaxed), 4);
+        let (restarts, last_restart) = metrics.updater_restarts();
+        assert_eq!(restarts, 3);
+        assert!(last_restart.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_supervise_stops_on_shutdown() {
+        let metrics = Metrics::default();
+        let shutdown = CancellationToken::new();
+        let task_shutdown = shutdown.clone();
+        let spawn = || {
+            let shutdown = task_shutdown.clone();
+            async move { shutdown.cancel() }
+        };
+
+        supervise(
+            "test",
+            spawn,
+            3,
+            Duration::ZERO,
+            || metrics.record_updater_restart(),
+            shutdown,
+        )
+        .await;
+
+        assert_eq!(metrics.updater_restarts(), (0, None));
+    }
+}